@@ -10,26 +10,46 @@ const ERR_END: &str     = "\x1b[0m";
 const LEXER_ERR: &str   = "Lexer error";
 const PARSER_ERR: &str  = "Parser error";
 
-/// Holds the two types of lexer errors.
+/// Holds the types of lexer errors.
+#[derive(Debug, PartialEq)]
 enum LexerErrType {
     InvalidToken(char),
     UnterminatedString,
+    UnterminatedComment,
+    InvalidEscape(char),
+    InvalidUnicodeEscape(String),
+    InvalidDigit(char),
+    MalformedNumber(String),
+    NumberOverflow(String),
 }
 
 impl fmt::Display for LexerErrType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let msg = match *self {
-            LexerErrType::UnterminatedString => format!("Unterminated string"),
+        let msg = match self {
+            LexerErrType::UnterminatedString => "Unterminated string".to_string(),
+            LexerErrType::UnterminatedComment => "Unterminated block comment".to_string(),
             LexerErrType::InvalidToken(car) => format!("Invalid Token: `{car}`"),
+            LexerErrType::InvalidEscape(car) => format!("Invalid escape: `\\{car}`"),
+            LexerErrType::InvalidUnicodeEscape(digits) => {
+                format!("Invalid unicode escape: `\\u{{{digits}}}`")
+            },
+            LexerErrType::InvalidDigit(car) => format!("Invalid digit in number: `{car}`"),
+            LexerErrType::MalformedNumber(num) => format!("Malformed number: `{num}`"),
+            LexerErrType::NumberOverflow(num) => format!("Number literal out of range: `{num}`"),
         };
         write!(f, "{ERR_START}{LEXER_ERR}{ERR_END}: {msg}")
     }
 }
 
-/// Holds the two types of parser errors.
+/// Holds the types of parser errors.
+#[derive(Debug, PartialEq)]
 enum ParserErrType {
     Expected(String),
     ExpecetedOneOf(Vec<String>),
+    NonAssociative(String),
+    InvalidFixity(i64),
+    NestingTooDeep(usize),
+    UndeclaredOperator(String),
 }
 
 impl fmt::Display for ParserErrType {
@@ -43,16 +63,29 @@ impl fmt::Display for ParserErrType {
                     if comma {
                         one_of += ", ";
                     }
-                    one_of += &tok;
+                    one_of += tok;
                     comma = true;
                 }
                 format!("Expected one of: {one_of}")
             },
+            ParserErrType::NonAssociative(op) => {
+                format!("Non-associative operator `{op}` cannot be chained")
+            },
+            ParserErrType::InvalidFixity(level) => {
+                format!("Fixity level {level} is not between 0 and 9")
+            },
+            ParserErrType::NestingTooDeep(limit) => {
+                format!("Nesting too deep: more than {limit} levels")
+            },
+            ParserErrType::UndeclaredOperator(op) => {
+                format!("Operator `{op}` has no fixity declaration")
+            },
         };
         write!(f, "{ERR_START}{PARSER_ERR}{ERR_END}: {msg}")
     }
 }
 
+#[derive(Debug, PartialEq)]
 pub struct LexerError {
     row: usize,
     col: usize,
@@ -69,7 +102,37 @@ pub fn new_unterminated_str(row: usize, col: usize) -> LexerError {
     LexerError { row, col, typ: LexerErrType::UnterminatedString }
 }
 
+/// Creates a LexerError struct for a block comment missing its closing `-)`.
+pub fn new_unterminated_comment(row: usize, col: usize) -> LexerError {
+    LexerError { row, col, typ: LexerErrType::UnterminatedComment }
+}
+
+/// Creates a LexerError struct for an unknown escape sequence in a string.
+pub fn new_invalid_escape(car: char, row: usize, col: usize) -> LexerError {
+    LexerError { row, col, typ: LexerErrType::InvalidEscape(car) }
+}
+
+/// Creates a LexerError struct for a malformed or out of range `\u{...}`.
+pub fn new_invalid_unicode_escape(digits: String, row: usize, col: usize) -> LexerError {
+    LexerError { row, col, typ: LexerErrType::InvalidUnicodeEscape(digits) }
+}
 
+/// Creates a LexerError struct for a digit which is not valid in its radix.
+pub fn new_invalid_digit(car: char, row: usize, col: usize) -> LexerError {
+    LexerError { row, col, typ: LexerErrType::InvalidDigit(car) }
+}
+
+/// Creates a LexerError struct for a number missing its digits, e.g. `0x`.
+pub fn new_malformed_number(num: String, row: usize, col: usize) -> LexerError {
+    LexerError { row, col, typ: LexerErrType::MalformedNumber(num) }
+}
+
+/// Creates a LexerError struct for a number too large for its type.
+pub fn new_number_overflow(num: String, row: usize, col: usize) -> LexerError {
+    LexerError { row, col, typ: LexerErrType::NumberOverflow(num) }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct ParserError {
     row: usize,
     col: usize,
@@ -86,6 +149,47 @@ pub fn new_parser_expected_one_of(
     ParserError { row, col, typ: ParserErrType::ExpecetedOneOf(val) }
 }
 
+pub fn new_parser_non_associative(row: usize, col: usize, op: String) -> ParserError {
+    ParserError { row, col, typ: ParserErrType::NonAssociative(op) }
+}
+
+pub fn new_parser_invalid_fixity(row: usize, col: usize, level: i64) -> ParserError {
+    ParserError { row, col, typ: ParserErrType::InvalidFixity(level) }
+}
+
+pub fn new_parser_nesting_too_deep(row: usize, col: usize, limit: usize) -> ParserError {
+    ParserError { row, col, typ: ParserErrType::NestingTooDeep(limit) }
+}
+
+pub fn new_parser_undeclared_operator(row: usize, col: usize, op: String) -> ParserError {
+    ParserError { row, col, typ: ParserErrType::UndeclaredOperator(op) }
+}
+
+/// SyntaxError is the first error found while lexing and parsing a fragment
+/// of source, such as a single expression.
+#[derive(Debug, PartialEq)]
+pub enum SyntaxError {
+    Lexer(LexerError),
+    Parser(ParserError),
+}
+
+impl SyntaxError {
+    pub fn show_error(&self, src: &str, input: &str) {
+        match self {
+            SyntaxError::Lexer(err) => err.show_error(src, input),
+            SyntaxError::Parser(err) => err.show_error(src, input),
+        }
+    }
+
+    /// Returns the 1-based `(row, col)` the error points at.
+    pub fn location(&self) -> (usize, usize) {
+        match self {
+            SyntaxError::Lexer(err) => (err.row, err.col),
+            SyntaxError::Parser(err) => (err.row, err.col),
+        }
+    }
+}
+
 impl LexerError {
     pub fn show_error(&self, src: &str, input: &str) {
         let padding = get_padding(self.row);
@@ -175,28 +279,33 @@ fn get_padding(row: usize) -> usize {
     1 + get_line_size(row)
 }
 
+/// Returns the text of the 1-based line `row` of `src`, or an empty string
+/// if `src` has no such line, as for an error without a location.
 fn get_line(src: &str, row: usize) -> String {
-    let mut line = String::new();
-    let acc_row = row - 1;
-    let mut cur_row = 0;
-    let mut ptr = 0;
-    
-    while acc_row != cur_row {
-        if src.chars().nth(ptr) == Some('\n') {
-            cur_row += 1;
-        }
-        ptr += 1;
+    let lines: Vec<&str> = src.split('\n').collect();
+    match row {
+        0 => String::new(),
+        _ => lines.get(row.saturating_sub(1)).copied().unwrap_or_default().to_string(),
     }
+}
 
-    let mut ended = false;
-    while !ended {
-        match src.chars().nth(ptr) {
-            Some('\n')  => ended = true,
-            Some(col)   => line.push(col),
-            _           => break,
-        }
-        ptr += 1;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gets_lines_by_row() {
+        let src = "let a = 1;\nlet b = 2;";
+        assert_eq!(get_line(src, 1), "let a = 1;");
+        assert_eq!(get_line(src, 2), "let b = 2;");
+        assert_eq!(get_line(src, 0), "");
+        assert_eq!(get_line(src, 3), "");
     }
 
-    return line;
+    #[test]
+    fn shows_errors_without_a_location() {
+        new_parser_expected(0, 0, "<factor>".to_string()).show_error("let x = -", "x.ivy");
+        new_parser_expected(9, 1, "<factor>".to_string()).show_error("let x = -", "x.ivy");
+        new_invalid_token('#', 0, 0).show_error("#", "x.ivy");
+    }
 }
@@ -1 +1,2 @@
+#[allow(clippy::module_inception)]
 pub mod errors;
\ No newline at end of file
@@ -0,0 +1,3 @@
+pub mod tokens;
+pub mod parser;
+pub mod errors;
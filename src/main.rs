@@ -1,15 +1,11 @@
-mod tokens;
-mod parser;
-mod errors;
-
-use crate::tokens::tokens::*;
-use crate::errors::errors::*;
-use crate::parser::ast::*;
-use crate::parser::parser::*;
+use parsley::tokens::tokens::*;
+use parsley::tokens::lexer::*;
+use parsley::parser::ast::*;
+use parsley::parser::parser::*;
 
 
 fn new_token(ttype: TokenType) -> Token {
-    Token {typ: ttype, row: 0, col: 0}
+    Token::new(ttype, 0, 0)
 }
 
 
@@ -17,16 +13,16 @@ fn factorial() -> Vec<Token> {
     vec![
         // fn factorial :: Int -> Int;
         new_token(TokenType::Fn),
-        new_token(TokenType::Symbol(format!("factorial"))),
+        new_token(TokenType::Symbol("factorial".to_string())),
         new_token(TokenType::DoubleColon),
-        new_token(TokenType::Symbol(format!("Int"))),
+        new_token(TokenType::Symbol("Int".to_string())),
         new_token(TokenType::Arrow),
-        new_token(TokenType::Symbol(format!("Int"))),
+        new_token(TokenType::Symbol("Int".to_string())),
         new_token(TokenType::Semicolon),
         
         // fn factorial (0) => 1;
         new_token(TokenType::Fn),
-        new_token(TokenType::Symbol(format!("factorial"))),
+        new_token(TokenType::Symbol("factorial".to_string())),
         new_token(TokenType::LParen),
         new_token(TokenType::Integer(0)),
         new_token(TokenType::RParen),
@@ -36,16 +32,16 @@ fn factorial() -> Vec<Token> {
         
         // fn factorial (n) => n * factorial(n - 1);
         new_token(TokenType::Fn),
-        new_token(TokenType::Symbol(format!("factorial"))),
+        new_token(TokenType::Symbol("factorial".to_string())),
         new_token(TokenType::LParen),
-        new_token(TokenType::Symbol(format!("n"))),
+        new_token(TokenType::Symbol("n".to_string())),
         new_token(TokenType::RParen),
         new_token(TokenType::EqArrow),
-        new_token(TokenType::Symbol(format!("n"))),
+        new_token(TokenType::Symbol("n".to_string())),
         new_token(TokenType::Star),
-        new_token(TokenType::Symbol(format!("factorial"))),
+        new_token(TokenType::Symbol("factorial".to_string())),
         new_token(TokenType::LParen),
-        new_token(TokenType::Symbol(format!("n"))),
+        new_token(TokenType::Symbol("n".to_string())),
         new_token(TokenType::Minus),
         new_token(TokenType::Integer(1)),
         new_token(TokenType::RParen),
@@ -53,9 +49,42 @@ fn factorial() -> Vec<Token> {
     ]
 }
 
+/// Lexes and parses the ivy file at `path`, printing the resulting tree and 
+/// every parser error found.
+fn run_file(path: &str, options: ParserOptions) {
+    let src = match std::fs::read_to_string(path) {
+        Ok(src) => src,
+        Err(err) => {
+            println!("could not read {path}: {err}");
+            return;
+        }
+    };
+
+    let tokens = match lex(&src) {
+        Ok(tokens) => tokens,
+        Err(err) => return err.show_error(&src, path),
+    };
+
+    let (tree, errors) = parse_with(tokens, options);
+    print_tree(tree);
+    for err in errors {
+        err.show_error(&src, path);
+    }
+}
+
 fn main() {
-    match parse(factorial()) {
-        Ok(tree) => print_tree(tree),
-        Err(_) => println!("parser error")
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = ParserOptions { 
+        asi: args.iter().any(|arg| arg == "--asi"), 
+        ..ParserOptions::default()
+    };
+    if let Some(path) = args.iter().find(|arg| !arg.starts_with("--")) {
+        return run_file(path, options);
+    }
+
+    let (tree, errors) = parse(factorial());
+    match errors.len() {
+        0 => print_tree(tree),
+        _ => println!("parser error")
     }
 }
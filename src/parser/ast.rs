@@ -4,8 +4,23 @@
 
 const TAB: &str = "  ";
 
-use crate::Token;
-use crate::TokenType;
+use crate::tokens::tokens::{Span, Token, TokenType};
+
+// ====================================
+// Decorators
+// ====================================
+
+// <decorator> ::= '@' <symbol> [ <tupleAny> ]? ;
+pub struct Decorator {
+    pub name: String,
+    pub args: Vec<Node>,
+    pub token: Box<Token>,
+    pub span: Span,
+}
+
+pub fn NewDecorator(tok: Token, name: String, args: Vec<Node>) -> Decorator {
+    Decorator { name, args, token: Box::new(tok), span: Span::default() }
+}
 
 // ====================================
 // Let Node
@@ -17,26 +32,29 @@ pub struct LetExpr {
     pub rhs: Box<Node>,
     pub token: Box<Token>,
     pub is_mut: bool,
-    pub ttype: Box<Option<Node>>
+    pub ttype: Box<Option<Node>>,
+    pub span: Span,
 }
 
 pub fn NewLetExpr(tok: Token, symbols: Vec<Node>, rhs: Node, ttype: Option<Node>) -> Node {
     Node::LetExpr(LetExpr { 
-        symbols: symbols, 
+        symbols, 
         rhs: Box::new(rhs), 
         token: Box::new(tok), 
         is_mut: false, 
-        ttype: Box::new(ttype)
+        ttype: Box::new(ttype),
+        span: Span::default(),
     })
 }
 
 pub fn NewLetMutExpr(tok: Token, symbols: Vec<Node>, rhs: Node, ttype: Option<Node>) -> Node {
     Node::LetExpr(LetExpr { 
-        symbols: symbols, 
+        symbols, 
         rhs: Box::new(rhs), 
         token: Box::new(tok),
         is_mut: true, 
-        ttype: Box::new(ttype)
+        ttype: Box::new(ttype),
+        span: Span::default(),
     })
 }
 
@@ -49,11 +67,13 @@ pub struct MutExpr {
     pub lhs: Box<Node>,
     pub rhs: Box<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewMutExpr(tok: Token, lhs: Node, rhs: Node) -> Node {
     Node::MutExpr(MutExpr { 
-        lhs: Box::new(lhs), rhs: Box::new(rhs), token: Box::new(tok) 
+        lhs: Box::new(lhs), rhs: Box::new(rhs), token: Box::new(tok),
+        span: Span::default(), 
     })
 }
 
@@ -67,49 +87,69 @@ pub struct FnAnon {
     pub type_out: Box<Option<Node>>,
     pub rhs: Box<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewFnAnon(tok: Token, arguments: Vec<Node>, type_out: Option<Node>, rhs: Node) -> Node {
     Node::FnAnon(FnAnon { 
-        arguments: arguments, 
+        arguments, 
         type_out: Box::new(type_out),
         rhs: Box::new(rhs), 
-        token: Box::new(tok), 
+        token: Box::new(tok),
+        span: Span::default(), 
     })
 }
 
-// <fnSignature> ::= 'fn' <symbol> '::' <typeFn> ;
+// <fnSignature> ::= 'fn' <symbol> [ <generics> ]? '::' <typeFn> [ <whereClause> ]? ;
 pub struct FnSignature {
     pub symbol: Box<Node>,
+    pub generics: Vec<Node>,
     pub ttype: Box<Node>,
+    pub where_clause: Vec<Node>,
     pub token: Box<Token>,
+    pub decorators: Vec<Decorator>,
+    pub span: Span,
 }
 
-pub fn NewFnSignature(tok: Token, symbol: Node, ttype: Node) -> Node {
+pub fn NewFnSignature(
+    tok: Token, symbol: Node, generics: Vec<Node>, ttype: Node, where_clause: Vec<Node>,
+) -> Node {
     Node::FnSignature(FnSignature { 
-        token: Box::new(tok), symbol: Box::new(symbol), ttype: Box::new(ttype),
+        token: Box::new(tok), symbol: Box::new(symbol), generics, ttype: Box::new(ttype),
+        where_clause,
+        decorators: Vec::new(),
+        span: Span::default(),
     })
 }
 
-// <fnDeclaration> ::= 'fn' <symbol> <fnArgs> [ ':' <typeFn> ]? '=>' <expression> ;
+// <fnDeclaration> ::= 'fn' <symbol> [ <generics> ]? <fnArgs> [ ':' <typeFn> ]? 
+//                     [ <whereClause> ]? '=>' <expression> ;
 pub struct FnDeclaration {
     pub symbol: Box<Node>,
+    pub generics: Vec<Node>,
     pub arguments: Vec<Node>,
     pub type_out: Box<Option<Node>>,
+    pub where_clause: Vec<Node>,
     pub rhs: Box<Node>,
     pub token: Box<Token>,
+    pub decorators: Vec<Decorator>,
+    pub span: Span,
 }
 
 pub fn NewFnDeclaration(
-    tok: Token, symbol: Node, rhs: Node,
-    arguments: Vec<Node>, type_out: Option<Node>, 
+    tok: Token, symbol: Node, generics: Vec<Node>, rhs: Node,
+    arguments: Vec<Node>, type_out: Option<Node>, where_clause: Vec<Node>,
 ) -> Node {
     Node::FnDeclaration(FnDeclaration { 
         symbol: Box::new(symbol), 
-        arguments: arguments, 
+        generics,
+        arguments, 
         type_out: Box::new(type_out), 
+        where_clause,
         rhs: Box::new(rhs), 
         token: Box::new(tok),
+        decorators: Vec::new(),
+        span: Span::default(),
     })
 }
 
@@ -118,15 +158,17 @@ pub fn NewFnDeclaration(
 // ====================================
 
 
-// <fnArgsTyped> ::= <symbol> [ ':' <typeFn> ] ? ;
+// <fnArgsTyped> ::= <pattern> [ ':' <typeFn> ] ? ;
 pub struct FnArgTyped {
-    pub symbol: Box<Node>,
+    pub pattern: Box<Node>,
     pub ttype: Box<Option<Node>>,
+    pub span: Span,
 }
 
-pub fn NewFnArgTyped(symbol: Node, ttype: Option<Node>) -> Node {
+pub fn NewFnArgTyped(pattern: Node, ttype: Option<Node>) -> Node {
     Node::FnArgTyped ( FnArgTyped{
-        symbol: Box::new(symbol), ttype: Box::new(ttype),
+        pattern: Box::new(pattern), ttype: Box::new(ttype),
+        span: Span::default(),
     })
 }
 
@@ -140,6 +182,7 @@ pub struct IfExpr {
     pub true_branch: Box<Node>,
     pub false_branch: Box<Option<Node>>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewIfExpr(
@@ -150,6 +193,7 @@ pub fn NewIfExpr(
         true_branch: Box::new(true_branch),
         false_branch: Box::new(false_branch),
         token: Box::new(tok),
+        span: Span::default(),
     })
 }
 
@@ -157,31 +201,57 @@ pub fn NewIfExpr(
 // Pub Expression
 // ===================================
 
-// <pubExpr> ::= 'pub' [ <fnSignature> | <fnDeclaration> | <typeExpr> | <structStmt> ] ;
+// <pubExpr> ::= 'pub' [ <fnSignature> | <fnDeclaration> | <typeExpr> | <structStmt> | <importStmt> ] ;
 pub struct PubExpr {
     pub rhs: Box<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewPubExpr (tok: Token, rhs: Node) -> Node {
-    Node::PubExpr( PubExpr{ token: Box::new(tok), rhs: Box::new(rhs) })
+    Node::PubExpr( PubExpr{ token: Box::new(tok), rhs: Box::new(rhs), span: Span::default() })
+}
+
+// ====================================
+// Generics
+// ====================================
+
+// <genericParam> ::= <symbol> ':' <typeCmpst> [ '+' <typeCmpst> ]* ;
+pub struct GenericParam {
+    pub symbol: Box<Node>,
+    pub bounds: Vec<Node>,
+    pub span: Span,
+}
+
+pub fn NewGenericParam(symbol: Node, bounds: Vec<Node>) -> Node {
+    Node::GenericParam(GenericParam { 
+        symbol: Box::new(symbol), bounds,
+        span: Span::default(),
+    })
 }
 
 // ====================================
 // Data Types 
 // ====================================
 
-// <dataDeclaration> ::= 'data' <symbol> '(' <typeVariants> ')' ;
+// <dataDeclaration> ::= 'data' <symbol> [ <generics> ]? [ <whereClause> ]? '(' <typeVariants> ')' ;
 pub struct DataDeclaration {
     pub symbol: Box<Node>,
     pub generics: Vec<Node>,
+    pub where_clause: Vec<Node>,
     pub variants: Vec<Node>,
     pub token: Box<Token>,
+    pub decorators: Vec<Decorator>,
+    pub span: Span,
 }
 
-pub fn NewDataDelcaration(tok: Token, symbol: Node,  generics: Vec<Node>, variants: Vec<Node>) -> Node {
+pub fn NewDataDelcaration(
+    tok: Token, symbol: Node, generics: Vec<Node>, where_clause: Vec<Node>, variants: Vec<Node>,
+) -> Node {
     Node::DataDeclaration(DataDeclaration {
-        token: Box::new(tok), symbol: Box::new(symbol), generics, variants
+        token: Box::new(tok), symbol: Box::new(symbol), generics, where_clause, variants,
+        decorators: Vec::new(),
+        span: Span::default(),
     })
 }
 
@@ -190,14 +260,55 @@ pub fn NewDataDelcaration(tok: Token, symbol: Node,  generics: Vec<Node>, varian
 pub struct DataItem {
     pub symbol: Box<Node>,
     pub ttype: Box<Node>,
+    pub span: Span,
 }
 
 pub fn NewDataItem(symbol: Node, ttype: Node) -> Node {
     Node::DataItem(DataItem { 
-        symbol: Box::new(symbol), ttype: Box::new(ttype)
+        symbol: Box::new(symbol), ttype: Box::new(ttype),
+        span: Span::default(),
     })
 }
 
+// ====================================
+// Type Aliases & Newtypes
+// ====================================
+
+// <typeAlias> ::= 'type' <symbol> [ <generics> ]? '=' <typeFn> ;
+pub struct TypeAlias {
+    pub symbol: Box<Node>,
+    pub generics: Vec<Node>,
+    pub ttype: Box<Node>,
+    pub token: Box<Token>,
+    pub decorators: Vec<Decorator>,
+    pub span: Span,
+}
+
+pub fn NewTypeAlias(tok: Token, symbol: Node, generics: Vec<Node>, ttype: Node) -> Node {
+    Node::TypeAlias(TypeAlias {
+        token: Box::new(tok), symbol: Box::new(symbol), generics, ttype: Box::new(ttype),
+        decorators: Vec::new(),
+        span: Span::default(),
+    })
+}
+
+// <newtypeExpr> ::= 'newtype' <symbol> [ <generics> ]? '=' <typeFn> ;
+pub struct Newtype {
+    pub symbol: Box<Node>,
+    pub generics: Vec<Node>,
+    pub ttype: Box<Node>,
+    pub token: Box<Token>,
+    pub decorators: Vec<Decorator>,
+    pub span: Span,
+}
+
+pub fn NewNewtype(tok: Token, symbol: Node, generics: Vec<Node>, ttype: Node) -> Node {
+    Node::Newtype(Newtype {
+        token: Box::new(tok), symbol: Box::new(symbol), generics, ttype: Box::new(ttype),
+        decorators: Vec::new(),
+        span: Span::default(),
+    })
+}
 
 // ====================================
 // Structs
@@ -207,22 +318,31 @@ pub fn NewDataItem(symbol: Node, ttype: Node) -> Node {
 pub struct StructAnon {
     pub fields: Vec<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewStructAnon(tok: Token, fields: Vec<Node>) -> Node {
-    Node::StructAnon(StructAnon { token: Box::new(tok), fields })
+    Node::StructAnon(StructAnon { token: Box::new(tok), fields, span: Span::default() })
 }
 
-// <structDeclaration> ::= 'struct' <symbol> '(' <stuctFields> ')' ;
+// <structDeclaration> ::= 'struct' <symbol> [ <generics> ]? [ <whereClause> ]? '(' <stuctFields> ')' ;
 pub struct StructDeclaration {
     pub symbol: Box<Node>,
+    pub generics: Vec<Node>,
+    pub where_clause: Vec<Node>,
     pub fields: Vec<Node>,
     pub token: Box<Token>,
+    pub decorators: Vec<Decorator>,
+    pub span: Span,
 }
 
-pub fn NewStructDeclaration(tok: Token, symbol: Node, fields: Vec<Node>) -> Node {
+pub fn NewStructDeclaration(
+    tok: Token, symbol: Node, generics: Vec<Node>, where_clause: Vec<Node>, fields: Vec<Node>,
+) -> Node {
     Node::StructDeclaration(StructDeclaration { 
-        symbol: Box::new(symbol), token: Box::new(tok), fields 
+        symbol: Box::new(symbol), token: Box::new(tok), generics, where_clause, fields,
+        decorators: Vec::new(),
+        span: Span::default(), 
     })
 }
 
@@ -231,14 +351,102 @@ pub fn NewStructDeclaration(tok: Token, symbol: Node, fields: Vec<Node>) -> Node
 pub struct StructField {
     pub symbol: Box<Node>,
     pub ttype: Box<Node>,
+    pub span: Span,
 }
 
 pub fn NewStructField(symbol: Node, ttype: Node) -> Node {
     Node::StructField(
-        StructField{ symbol: Box::new(symbol), ttype: Box::new(ttype)} 
+        StructField{ symbol: Box::new(symbol), ttype: Box::new(ttype), span: Span::default()} 
     )
 }
 
+// <structLiteral> ::= <operand> '(' <fieldInits> ')' 
+//                   | <operand> '{' <fieldInits> '}' ;
+pub struct StructLiteral {
+    pub name: Box<Node>,
+    pub fields: Vec<Node>,
+    pub token: Box<Token>,
+    pub span: Span,
+}
+
+pub fn NewStructLiteral(tok: Token, name: Node, fields: Vec<Node>) -> Node {
+    Node::StructLiteral(StructLiteral { 
+        token: Box::new(tok), name: Box::new(name), fields, span: Span::default(),
+    })
+}
+
+// <structUpdate> ::= '{' <expression> 'with' <fieldInits> '}' ;
+pub struct StructUpdate {
+    pub base: Box<Node>,
+    pub fields: Vec<Node>,
+    pub token: Box<Token>,
+    pub span: Span,
+}
+
+pub fn NewStructUpdate(tok: Token, base: Node, fields: Vec<Node>) -> Node {
+    Node::StructUpdate(StructUpdate { 
+        token: Box::new(tok), base: Box::new(base), fields, span: Span::default(),
+    })
+}
+
+// <fieldInits> ::= <fieldInit> [ ',' <fieldInit> ]* [ ',' ]? ;
+// <fieldInit>  ::= <symbol> [ ( ':' | '=' ) <expression> ]? ;
+pub struct FieldInit {
+    pub symbol: Box<Node>,
+    pub value: Box<Option<Node>>,
+    pub span: Span,
+}
+
+pub fn NewFieldInit(symbol: Node, value: Option<Node>) -> Node {
+    Node::FieldInit(FieldInit { 
+        symbol: Box::new(symbol), value: Box::new(value), span: Span::default(),
+    })
+}
+
+// ====================================
+// Traits
+// ====================================
+
+// <traitDeclaration> ::= 'trait' <symbol> [ <generics> ]? [ <whereClause> ]? '(' [ <fnItem> ';' ]* ')' ;
+// <fnItem>           ::= [ <decorator> ]* [ <fnSignature> | <fnDeclaration> ] ;
+pub struct TraitDeclaration {
+    pub symbol: Box<Node>,
+    pub generics: Vec<Node>,
+    pub where_clause: Vec<Node>,
+    pub methods: Vec<Node>,
+    pub token: Box<Token>,
+    pub decorators: Vec<Decorator>,
+    pub span: Span,
+}
+
+pub fn NewTraitDeclaration(
+    tok: Token, symbol: Node, generics: Vec<Node>, where_clause: Vec<Node>, methods: Vec<Node>,
+) -> Node {
+    Node::TraitDeclaration(TraitDeclaration {
+        token: Box::new(tok), symbol: Box::new(symbol), generics, where_clause, methods,
+        decorators: Vec::new(),
+        span: Span::default(),
+    })
+}
+
+// <implBlock> ::= 'impl' [ <typeCmpst> 'for' ]? <typeCmpst> '(' [ <fnItem> ';' ]* ')' ;
+pub struct ImplBlock {
+    pub ttrait: Box<Option<Node>>,
+    pub ttype: Box<Node>,
+    pub items: Vec<Node>,
+    pub token: Box<Token>,
+    pub decorators: Vec<Decorator>,
+    pub span: Span,
+}
+
+pub fn NewImplBlock(tok: Token, ttrait: Option<Node>, ttype: Node, items: Vec<Node>) -> Node {
+    Node::ImplBlock(ImplBlock {
+        token: Box::new(tok), ttrait: Box::new(ttrait), ttype: Box::new(ttype), items,
+        decorators: Vec::new(),
+        span: Span::default(),
+    })
+}
+
 // ====================================
 // Package Statement
 // ====================================
@@ -247,24 +455,39 @@ pub fn NewStructField(symbol: Node, ttype: Node) -> Node {
 pub struct Package {
     pub token: Box<Token>,
     pub rhs: Box<Node>,
+    pub span: Span,
 }
 
 pub fn NewPackage (rhs: Node, token: Token) -> Node {
-    Node::Package(Package { token: Box::new(token), rhs: Box::new(rhs) })
+    Node::Package(Package { token: Box::new(token), rhs: Box::new(rhs), span: Span::default() })
 }
 
 // ====================================
 // Import Statement
 // ====================================
 
-// <importStmt>    ::= 'import' [ <string> | <tupleStrings> ] ;
+// <importStmt>    ::= 'import' [ <string> [ 'as' <symbol> ]? [ <importItems> ]? | <tupleStrings> ] ;
+// <importItems>   ::= [ 'hiding' ]? '(' <importItem> [ ',' <importItem> ]* [ ',' ]? ')' ;
 pub struct Import {
     pub token: Box<Token>,
     pub rhs: Vec<Node>,
+    pub alias: Box<Option<Node>>,
+    pub items: Vec<Node>,
+    pub hiding: Vec<Node>,
+    pub span: Span,
 }
 
 pub fn NewImport (rhs: Vec<Node>, token: Token) -> Node {
-    Node::Import(Import { token: Box::new(token), rhs })
+    NewImportWith(rhs, None, Vec::new(), Vec::new(), token)
+}
+
+pub fn NewImportWith(
+    rhs: Vec<Node>, alias: Option<Node>, items: Vec<Node>, hiding: Vec<Node>, token: Token,
+) -> Node {
+    Node::Import(Import { 
+        token: Box::new(token), rhs, alias: Box::new(alias), items, hiding, 
+        span: Span::default(),
+    })
 }
 
 // ====================================
@@ -276,24 +499,29 @@ pub struct MatchExpression {
     pub lhs: Box<Node>,
     pub branches: Vec<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewMatchExpression(tok: Token, lhs: Node, branches: Vec<Node>) -> Node {
     Node::MatchExpression(
-        MatchExpression { token: Box::new(tok), lhs: Box::new(lhs), branches 
+        MatchExpression { token: Box::new(tok), lhs: Box::new(lhs), branches,
+        span: Span::default(), 
     })
 }
 
-// <matchBranch>   ::= '|' <expression> '->' <expression>
+// <matchBranch>   ::= '|' <pattern> [ 'if' <or> ]? '->' <expression>
 pub struct MatchBranch {
     pub lhs: Box<Node>,
+    pub guard: Box<Option<Node>>,
     pub rhs: Box<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
-pub fn NewMatchBranch(tok: Token, lhs: Node, rhs: Node) -> Node {
+pub fn NewMatchBranch(tok: Token, lhs: Node, guard: Option<Node>, rhs: Node) -> Node {
     Node::MatchBranch(MatchBranch { 
-        token: Box::new(tok), lhs: Box::new(lhs), rhs: Box::new(rhs) 
+        token: Box::new(tok), lhs: Box::new(lhs), guard: Box::new(guard), 
+        rhs: Box::new(rhs), span: Span::default(), 
     })
 }
 
@@ -306,20 +534,127 @@ pub fn NewMatchBranch(tok: Token, lhs: Node, rhs: Node) -> Node {
 pub struct ListLiteral {
     pub items: Vec<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewListExpression(tok: Token, items: Vec<Node>) -> Node {
-    Node::ListLiteral(ListLiteral { token: Box::new(tok), items })
+    Node::ListLiteral(ListLiteral { token: Box::new(tok), items, span: Span::default() })
 }
 
 // <listSplit>     ::= '[ <symbol> '|' <symbol> ']' ;
 pub struct ListSplit {
     pub head: Box<Node>,
     pub tail: Box<Node>,
+    pub span: Span,
 }
 
 pub fn NewListSplit(head: Node, tail: Node) -> Node {
-    Node::ListSplit(ListSplit {head: Box::new(head), tail: Box::new(tail)})
+    Node::ListSplit(ListSplit {head: Box::new(head), tail: Box::new(tail), span: Span::default()})
+}
+
+// <listComp>   ::= '[' <expression> '|' <compClause> [ ',' <compClause> ]* ']' ;
+// <compClause> ::= <generator> | <expression> ;
+pub struct ListComprehension {
+    pub expr: Box<Node>,
+    pub clauses: Vec<Node>,
+    pub token: Box<Token>,
+    pub span: Span,
+}
+
+pub fn NewListComprehension(tok: Token, expr: Node, clauses: Vec<Node>) -> Node {
+    Node::ListComprehension(ListComprehension { 
+        token: Box::new(tok), expr: Box::new(expr), clauses, span: Span::default(),
+    })
+}
+
+// <generator>  ::= <pattern> '<-' <expression> ;
+pub struct Generator {
+    pub pattern: Box<Node>,
+    pub iter: Box<Node>,
+    pub token: Box<Token>,
+    pub span: Span,
+}
+
+pub fn NewGenerator(tok: Token, pattern: Node, iter: Node) -> Node {
+    Node::Generator(Generator { 
+        token: Box::new(tok), pattern: Box::new(pattern), iter: Box::new(iter),
+        span: Span::default(),
+    })
+}
+
+// ====================================
+// Patterns
+// ====================================
+
+// Literal and binding patterns are plain `Atom`s.
+
+// <wildcard> ::= '_' ;
+pub struct WildcardPattern {
+    pub token: Token,
+    pub span: Span,
+}
+
+pub fn NewWildcardPattern(tok: Token) -> Node {
+    Node::WildcardPattern(WildcardPattern { span: tok.span, token: tok })
+}
+
+// <constructorPattern> ::= <symbol> '(' [ <pattern> [ ',' <pattern> ]* ]? ')' ;
+pub struct ConstructorPattern {
+    pub name: Box<Node>,
+    pub args: Vec<Node>,
+    pub span: Span,
+}
+
+pub fn NewConstructorPattern(name: Node, args: Vec<Node>) -> Node {
+    Node::ConstructorPattern(ConstructorPattern { 
+        name: Box::new(name), args, span: Span::default(),
+    })
+}
+
+// <tuplePattern> ::= '(' <pattern> ',' <pattern> [ ',' <pattern> ]* ')' ;
+pub struct TuplePattern {
+    pub items: Vec<Node>,
+    pub span: Span,
+}
+
+pub fn NewTuplePattern(items: Vec<Node>) -> Node {
+    Node::TuplePattern(TuplePattern { items, span: Span::default() })
+}
+
+// <listPattern> ::= '[' [ <asPattern> [ ',' <asPattern> ]* [ '|' <asPattern> ]? ]? ']' ;
+pub struct ListPattern {
+    pub items: Vec<Node>,
+    pub rest: Box<Option<Node>>,
+    pub span: Span,
+}
+
+pub fn NewListPattern(items: Vec<Node>, rest: Option<Node>) -> Node {
+    Node::ListPattern(ListPattern { items, rest: Box::new(rest), span: Span::default() })
+}
+
+// <asPattern> ::= <patternAtom> [ 'as' <symbol> ]? ;
+pub struct AsPattern {
+    pub pattern: Box<Node>,
+    pub symbol: Box<Node>,
+    pub token: Box<Token>,
+    pub span: Span,
+}
+
+pub fn NewAsPattern(tok: Token, pattern: Node, symbol: Node) -> Node {
+    Node::AsPattern(AsPattern { 
+        token: Box::new(tok), pattern: Box::new(pattern), symbol: Box::new(symbol),
+        span: Span::default(),
+    })
+}
+
+// <pattern> ::= <asPattern> [ '|' <asPattern> ]* ;
+pub struct OrPattern {
+    pub patterns: Vec<Node>,
+    pub span: Span,
+}
+
+pub fn NewOrPattern(patterns: Vec<Node>) -> Node {
+    Node::OrPattern(OrPattern { patterns, span: Span::default() })
 }
 
 // ====================================
@@ -330,52 +665,58 @@ pub fn NewListSplit(head: Node, tail: Node) -> Node {
 pub struct TypeFn {
     pub lhs: Box<Node>,
     pub rhs: Box<Node>,
-    pub token: Box<Token>
+    pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewTypeFn(lhs: Node, rhs: Node, token: Token) -> Node {
     Node::TypeFn(TypeFn{ 
         lhs: Box::new(lhs), 
         rhs: Box::new(rhs), 
-        token: Box::new(token)
+        token: Box::new(token),
+        span: Span::default(),
     })
 }
 
 // <typeLst>   ::= <typeTuple> | '[' <typeFn> ']' ;
 pub struct TypeLst {
     pub ttype: Box<Node>,
-    pub token: Box<Token>
+    pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewTypeLst(ttype: Node, token: Token) -> Node { 
-    Node::TypeLst(TypeLst { ttype: Box::new(ttype), token: Box::new(token) }) 
+    Node::TypeLst(TypeLst { ttype: Box::new(ttype), token: Box::new(token), span: Span::default() }) 
 }
 
 // <typeTuple> ::= <typeCmpst> | '(' <typeFn> [ ',' <typeFn> ]* ')' ;
 pub struct TypeTuple {
     pub ttypes: Vec<Node>,
+    pub span: Span,
 }
 
-pub fn NewTypeTuple(ttypes: Vec<Node>) -> Node { Node::TypeTuple(TypeTuple { ttypes }) }
+pub fn NewTypeTuple(ttypes: Vec<Node>) -> Node { Node::TypeTuple(TypeTuple { ttypes, span: Span::default() }) }
 
 // <typeCmpst> ::= <type>      | <symbol> '<' [ <typeFn> [ ',' <typeFn> ]* ] '>' ;
 pub struct TypeCmpst {
     pub ttype: Box<Node>,
     pub items: Vec<Node>,
+    pub span: Span,
 }
 
 pub fn NewTypeCmpst(ttype: Node, items: Vec<Node>) -> Node {
-    Node::TypeCmpst(TypeCmpst{ ttype: Box::new(ttype), items })
+    Node::TypeCmpst(TypeCmpst{ ttype: Box::new(ttype), items, span: Span::default() })
 }
 
 // <type>      ::= [ 'mut' ]? <symbol> | <typeFn> ;
 pub struct Ttype {
     pub symbol: Box<Node>,
     pub is_mut: bool,
+    pub span: Span,
 }
 
 pub fn NewTtype(symbol: Node, is_mut: bool) -> Node {
-    Node::Ttype(Ttype { symbol: Box::new(symbol), is_mut })
+    Node::Ttype(Ttype { symbol: Box::new(symbol), is_mut, span: Span::default() })
 }
 
 
@@ -388,11 +729,33 @@ pub struct WhileExpression {
     pub cond: Box<Node>,
     pub statements: Vec<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewWhileExpression(tok: Token, cond: Node, statements: Vec<Node>) -> Node {
     Node::WhileExpression(WhileExpression { 
-        token: Box::new(tok), cond: Box::new(cond), statements 
+        token: Box::new(tok), cond: Box::new(cond), statements,
+        span: Span::default(), 
+    })
+}
+
+// ====================================
+// For Expressions
+// ====================================
+
+// <forExpr>       ::= 'for' <binder> 'in' <expression> '{' [ <statement> ]* '}' ;
+pub struct ForExpression {
+    pub binder: Vec<Node>,
+    pub iter: Box<Node>,
+    pub statements: Vec<Node>,
+    pub token: Box<Token>,
+    pub span: Span,
+}
+
+pub fn NewForExpression(tok: Token, binder: Vec<Node>, iter: Node, statements: Vec<Node>) -> Node {
+    Node::ForExpression(ForExpression {
+        token: Box::new(tok), binder, iter: Box::new(iter), statements,
+        span: Span::default(),
     })
 }
 
@@ -404,10 +767,11 @@ pub fn NewWhileExpression(tok: Token, cond: Node, statements: Vec<Node>) -> Node
 pub struct DoExpression {
     pub token: Box<Token>,
     pub statements: Vec<Node>,
+    pub span: Span,
 }
 
 pub fn NewDoExpression(tok: Token, statements: Vec<Node> ) -> Node {
-    Node::DoExpression(DoExpression { token: Box::new(tok), statements })
+    Node::DoExpression(DoExpression { token: Box::new(tok), statements, span: Span::default() })
 }
 
 // ====================================
@@ -418,11 +782,13 @@ pub fn NewDoExpression(tok: Token, statements: Vec<Node> ) -> Node {
 pub struct ReturnExpression {
     pub value: Box<Node>,
     pub token: Box<Token>,
+    pub span: Span,
 }
 
 pub fn NewReturnExpression(tok: Token, value: Node) -> Node {
     Node::ReturnExpression(ReturnExpression {
-        token: Box::new(tok), value: Box::new(value)
+        token: Box::new(tok), value: Box::new(value),
+        span: Span::default(),
     })
 }
 
@@ -434,69 +800,151 @@ pub struct BinaryExpression {
     pub lhs: Box<Node>,
     pub rhs: Box<Node>,
     pub token: Token,
+    pub span: Span,
 }
 
 pub fn NewBinaryExpression(tok: Token, lhs: Node, rhs: Node) -> Node {
     Node::BinaryExpression(BinaryExpression {
-        token: tok, lhs: Box::new(lhs), rhs: Box::new(rhs)
+        token: tok, lhs: Box::new(lhs), rhs: Box::new(rhs),
+        span: Span::default(),
     })
 }
 
 pub fn copy_token(tok: &Token) -> Token {
     Token {
-        col: tok.col, row: tok.row, typ: tok.typ.clone()
+        col: tok.col, row: tok.row, typ: tok.typ.clone(),
+        span: tok.span,
+        leading: tok.leading.clone(), trailing: tok.trailing.clone(),
+        newline: tok.newline,
     }
 }
 
 pub struct UnaryExpression {
     pub rhs: Box<Node>,
     pub token: Token,
+    pub span: Span,
 }
 
 pub fn NewUnaryExpression(tok: Token, rhs: Node) -> Node {
-    Node::UnaryExpression(UnaryExpression { token: tok, rhs: Box::new(rhs) })
+    Node::UnaryExpression(UnaryExpression { token: tok, rhs: Box::new(rhs), span: Span::default() })
+}
+
+// ====================================
+// Pipelines & Composition
+// ====================================
+
+// <pipe> ::= <operand> '|>' <operand> ;
+pub struct Pipe {
+    pub lhs: Box<Node>,
+    pub rhs: Box<Node>,
+    pub token: Token,
+    pub span: Span,
+}
+
+pub fn NewPipe(tok: Token, lhs: Node, rhs: Node) -> Node {
+    Node::Pipe(Pipe {
+        token: tok, lhs: Box::new(lhs), rhs: Box::new(rhs),
+        span: Span::default(),
+    })
+}
+
+// <compose> ::= <operand> [ '>>' | '<<' ] <operand> ;
+pub struct Compose {
+    pub lhs: Box<Node>,
+    pub rhs: Box<Node>,
+    pub token: Token,
+    pub span: Span,
+}
+
+pub fn NewCompose(tok: Token, lhs: Node, rhs: Node) -> Node {
+    Node::Compose(Compose {
+        token: tok, lhs: Box::new(lhs), rhs: Box::new(rhs),
+        span: Span::default(),
+    })
+}
+
+// ====================================
+// Ranges
+// ====================================
+
+// <range> ::= <operand> [ '..' | '..=' ] <operand> ;
+pub struct Range {
+    pub lhs: Box<Node>,
+    pub rhs: Box<Node>,
+    pub inclusive: bool,
+    pub token: Token,
+    pub span: Span,
+}
+
+pub fn NewRange(tok: Token, lhs: Node, rhs: Node) -> Node {
+    Node::Range(Range {
+        inclusive: tok.typ == TokenType::DotDotEq,
+        token: tok, lhs: Box::new(lhs), rhs: Box::new(rhs),
+        span: Span::default(),
+    })
+}
+
+// ====================================
+// Fixity Declarations
+// ====================================
+
+// <fixityDecl> ::= ( 'infixl' | 'infixr' | 'infix' ) <integer> <operator> [ ',' <operator> ]* ;
+pub struct FixityDeclaration {
+    pub level: Box<Node>,
+    pub operators: Vec<Node>,
+    pub token: Box<Token>,
+    pub span: Span,
+}
+
+pub fn NewFixityDeclaration(tok: Token, level: Node, operators: Vec<Node>) -> Node {
+    Node::FixityDeclaration(FixityDeclaration {
+        token: Box::new(tok), level: Box::new(level), operators,
+        span: Span::default(),
+    })
 }
 
 // ====================================
 // Calls
 // ====================================
 
-// <call> ::= <access> | <symbol> <tupleAny> ;
+// <call> ::= <operand> <tupleAny> ;
 pub struct Call {
     pub lhs: Box<Node>,
     pub args: Vec<Node>,
+    pub span: Span,
 }
 
 pub fn NewCall(lhs: Node, args: Vec<Node>) -> Node { 
-    Node::Call(Call{ lhs: Box::new(lhs), args }) 
+    Node::Call(Call{ lhs: Box::new(lhs), args, span: Span::default() }) 
 }
 
 // ====================================
 // Access
 // ====================================
 
-// <access> ::= <symbol> '.' <symbol>
-//            | <symbol> '.' <access>
-//            | <symbol> '.' <call>
+// <access> ::= <operand> '.' <symbol> ;
 pub struct Access {
     pub lhs: Box<Node>,
     pub rhs: Box<Node>,
+    pub span: Span,
 }
 
 pub fn NewAccess(lhs: Node, rhs: Node) -> Node {
-    Node::Access(Access{ lhs: Box::new(lhs), rhs: Box::new(rhs) })
+    Node::Access(Access{ lhs: Box::new(lhs), rhs: Box::new(rhs), span: Span::default() })
 }
 
-// <access> ::= <symbol> '[' <expression> ']' 
+// <accessIndex> ::= <operand> '[' <expression> ']' 
 pub struct AccessIndex {
     pub symbol: Box<Node>,
     pub index: Box<Node>,
+    pub span: Span,
 }
 
 pub fn NewAccessIndex(symbol: Node, index: Node) -> Node {
     Node::AccessIndex(AccessIndex {
         symbol: Box::new(symbol),
-        index: Box::new(index)
+        index: Box::new(index),
+        span: Span::default(),
     })
 }
 
@@ -507,28 +955,31 @@ pub fn NewAccessIndex(symbol: Node, index: Node) -> Node {
 // <tupleAny>      ::= '(' <expression> [ ','  <expression> ]* ')' ;
 pub struct TupleAny {
     pub items: Vec<Node>,
+    pub span: Span,
 }
 
 pub fn NewTupleAny(items: Vec<Node>) -> Node {
-    Node::TupleAny( TupleAny{ items })
+    Node::TupleAny( TupleAny{ items, span: Span::default() })
 }
 
 // <tupleSymbols>  ::= '(' <symbol> [ ',' <symbol> ]* [ ',' ]? ')' ;
 pub struct TupleSymbols {
     pub items: Vec<Node>,
+    pub span: Span,
 }
 
 pub fn NewTupleSymbols(items: Vec<Node>) -> Node {
-    Node::TupleSymbols( TupleSymbols{ items })
+    Node::TupleSymbols( TupleSymbols{ items, span: Span::default() })
 }
 
 // <tupleStrings>  ::= '(' <string> [ ',' <string> ]* [ ',' ]? ')' ;
 pub struct TupleString {
     pub items: Vec<Node>,
+    pub span: Span,
 }
 
 pub fn NewTupleString(items: Vec<Node>) -> Node {
-    Node::TupleString( TupleString{ items })
+    Node::TupleString( TupleString{ items, span: Span::default() })
 }
 
 // ====================================
@@ -537,18 +988,44 @@ pub fn NewTupleString(items: Vec<Node>) -> Node {
 
 pub struct Atom {
     pub token: Token,
+    pub span: Span,
 }
 
 pub fn NewAtom(tok: Token) -> Node { 
-    Node::Atom(Atom {token: tok }) 
+    Node::Atom(Atom { span: tok.span, token: tok }) 
+}
+
+// <unit> ::= '(' ')' ;
+pub struct Unit {
+    pub token: Token,
+    pub span: Span,
+}
+
+pub fn NewUnit(tok: Token) -> Node {
+    Node::Unit(Unit { span: tok.span, token: tok })
+}
+
+// ====================================
+// Errors
+// ====================================
+
+// Stands in for a statement which failed to parse, covering the tokens that
+// were skipped while recovering.
+pub struct Error {
+    pub span: Span,
+}
+
+pub fn NewError() -> Node {
+    Node::Error(Error { span: Span::default() })
 }
 
 pub struct Root {
-    children: Vec<Node>,
+    pub children: Vec<Node>,
+    pub span: Span,
 }
 
 pub fn NewRootNode(children: Vec<Node>) -> Node {
-    Node::Root( Root { children })
+    Node::Root( Root { children, span: Span::default() })
 }
 
 pub enum Node {
@@ -572,13 +1049,23 @@ pub enum Node {
     PubExpr(PubExpr),
     
     // [ ] Data Type Expression
+    GenericParam(GenericParam),
     DataDeclaration(DataDeclaration),
     DataItem(DataItem),
+    TypeAlias(TypeAlias),
+    Newtype(Newtype),
     
     // [ ] Structs
     StructAnon(StructAnon),
     StructDeclaration(StructDeclaration),
     StructField(StructField),
+    StructLiteral(StructLiteral),
+    StructUpdate(StructUpdate),
+    FieldInit(FieldInit),
+
+    // [x] Traits
+    TraitDeclaration(TraitDeclaration),
+    ImplBlock(ImplBlock),
 
     // [ ] Import & Package
     Package(Package),
@@ -591,10 +1078,23 @@ pub enum Node {
     // [ ] List Expressions
     ListLiteral(ListLiteral),
     ListSplit(ListSplit),
+    ListComprehension(ListComprehension),
+    Generator(Generator),
+
+    // [x] Patterns
+    WildcardPattern(WildcardPattern),
+    ConstructorPattern(ConstructorPattern),
+    TuplePattern(TuplePattern),
+    ListPattern(ListPattern),
+    AsPattern(AsPattern),
+    OrPattern(OrPattern),
 
     // [ ] While Expressions 
     WhileExpression(WhileExpression),
 
+    // [x] For Expressions
+    ForExpression(ForExpression),
+
     // [ ] Do Expressions
     DoExpression(DoExpression),
 
@@ -612,8 +1112,18 @@ pub enum Node {
     // [ ] Binary & Unary Expressions
     BinaryExpression(BinaryExpression),
     UnaryExpression(UnaryExpression),
+
+    // [x] Pipelines & Composition
+    Pipe(Pipe),
+    Compose(Compose),
+
+    // [x] Ranges
+    Range(Range),
+
+    // [x] Fixity Declarations
+    FixityDeclaration(FixityDeclaration),
     
-    // [ ] Call & Access Expressions
+    // [x] Call & Access Expressions
     Call(Call),
     Access(Access),
     AccessIndex(AccessIndex),
@@ -625,6 +1135,160 @@ pub enum Node {
     
     // [x] Atoms
     Atom(Atom),
+    Unit(Unit),
+
+    // [x] Errors
+    Error(Error),
+}
+
+impl Node {
+    /// Returns the span of source text the node was parsed from.
+    pub fn span(&self) -> Span {
+        match self {
+            Node::Root(node) => node.span,
+            Node::LetExpr(node) => node.span,
+            Node::MutExpr(node) => node.span,
+            Node::FnAnon(node) => node.span,
+            Node::FnSignature(node) => node.span,
+            Node::FnDeclaration(node) => node.span,
+            Node::IfExpr(node) => node.span,
+            Node::PubExpr(node) => node.span,
+            Node::GenericParam(node) => node.span,
+            Node::DataDeclaration(node) => node.span,
+            Node::DataItem(node) => node.span,
+            Node::TypeAlias(node) => node.span,
+            Node::Newtype(node) => node.span,
+            Node::StructAnon(node) => node.span,
+            Node::StructDeclaration(node) => node.span,
+            Node::StructField(node) => node.span,
+            Node::StructLiteral(node) => node.span,
+            Node::StructUpdate(node) => node.span,
+            Node::FieldInit(node) => node.span,
+            Node::TraitDeclaration(node) => node.span,
+            Node::ImplBlock(node) => node.span,
+            Node::Package(node) => node.span,
+            Node::Import(node) => node.span,
+            Node::MatchExpression(node) => node.span,
+            Node::MatchBranch(node) => node.span,
+            Node::ListLiteral(node) => node.span,
+            Node::ListSplit(node) => node.span,
+            Node::ListComprehension(node) => node.span,
+            Node::Generator(node) => node.span,
+            Node::WildcardPattern(node) => node.span,
+            Node::ConstructorPattern(node) => node.span,
+            Node::TuplePattern(node) => node.span,
+            Node::ListPattern(node) => node.span,
+            Node::AsPattern(node) => node.span,
+            Node::OrPattern(node) => node.span,
+            Node::WhileExpression(node) => node.span,
+            Node::ForExpression(node) => node.span,
+            Node::DoExpression(node) => node.span,
+            Node::ReturnExpression(node) => node.span,
+            Node::TypeFn(node) => node.span,
+            Node::TypeLst(node) => node.span,
+            Node::TypeTuple(node) => node.span,
+            Node::TypeCmpst(node) => node.span,
+            Node::Ttype(node) => node.span,
+            Node::FnArgTyped(node) => node.span,
+            Node::BinaryExpression(node) => node.span,
+            Node::UnaryExpression(node) => node.span,
+            Node::Pipe(node) => node.span,
+            Node::Compose(node) => node.span,
+            Node::Range(node) => node.span,
+            Node::FixityDeclaration(node) => node.span,
+            Node::Call(node) => node.span,
+            Node::Access(node) => node.span,
+            Node::AccessIndex(node) => node.span,
+            Node::TupleAny(node) => node.span,
+            Node::TupleSymbols(node) => node.span,
+            Node::TupleString(node) => node.span,
+            Node::Atom(node) => node.span,
+            Node::Unit(node) => node.span,
+            Node::Error(node) => node.span,
+        }
+    }
+
+    /// Returns the decorators of a declaration which can carry them, looking
+    /// through `pub`.
+    pub fn decorators_mut(&mut self) -> Option<&mut Vec<Decorator>> {
+        match self {
+            Node::PubExpr(node) => node.rhs.decorators_mut(),
+            Node::FnSignature(node) => Some(&mut node.decorators),
+            Node::FnDeclaration(node) => Some(&mut node.decorators),
+            Node::DataDeclaration(node) => Some(&mut node.decorators),
+            Node::TypeAlias(node) => Some(&mut node.decorators),
+            Node::Newtype(node) => Some(&mut node.decorators),
+            Node::StructDeclaration(node) => Some(&mut node.decorators),
+            Node::TraitDeclaration(node) => Some(&mut node.decorators),
+            Node::ImplBlock(node) => Some(&mut node.decorators),
+            _ => None,
+        }
+    }
+
+    pub fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Node::Root(node) => &mut node.span,
+            Node::LetExpr(node) => &mut node.span,
+            Node::MutExpr(node) => &mut node.span,
+            Node::FnAnon(node) => &mut node.span,
+            Node::FnSignature(node) => &mut node.span,
+            Node::FnDeclaration(node) => &mut node.span,
+            Node::IfExpr(node) => &mut node.span,
+            Node::PubExpr(node) => &mut node.span,
+            Node::GenericParam(node) => &mut node.span,
+            Node::DataDeclaration(node) => &mut node.span,
+            Node::DataItem(node) => &mut node.span,
+            Node::TypeAlias(node) => &mut node.span,
+            Node::Newtype(node) => &mut node.span,
+            Node::StructAnon(node) => &mut node.span,
+            Node::StructDeclaration(node) => &mut node.span,
+            Node::StructField(node) => &mut node.span,
+            Node::StructLiteral(node) => &mut node.span,
+            Node::StructUpdate(node) => &mut node.span,
+            Node::FieldInit(node) => &mut node.span,
+            Node::TraitDeclaration(node) => &mut node.span,
+            Node::ImplBlock(node) => &mut node.span,
+            Node::Package(node) => &mut node.span,
+            Node::Import(node) => &mut node.span,
+            Node::MatchExpression(node) => &mut node.span,
+            Node::MatchBranch(node) => &mut node.span,
+            Node::ListLiteral(node) => &mut node.span,
+            Node::ListSplit(node) => &mut node.span,
+            Node::ListComprehension(node) => &mut node.span,
+            Node::Generator(node) => &mut node.span,
+            Node::WildcardPattern(node) => &mut node.span,
+            Node::ConstructorPattern(node) => &mut node.span,
+            Node::TuplePattern(node) => &mut node.span,
+            Node::ListPattern(node) => &mut node.span,
+            Node::AsPattern(node) => &mut node.span,
+            Node::OrPattern(node) => &mut node.span,
+            Node::WhileExpression(node) => &mut node.span,
+            Node::ForExpression(node) => &mut node.span,
+            Node::DoExpression(node) => &mut node.span,
+            Node::ReturnExpression(node) => &mut node.span,
+            Node::TypeFn(node) => &mut node.span,
+            Node::TypeLst(node) => &mut node.span,
+            Node::TypeTuple(node) => &mut node.span,
+            Node::TypeCmpst(node) => &mut node.span,
+            Node::Ttype(node) => &mut node.span,
+            Node::FnArgTyped(node) => &mut node.span,
+            Node::BinaryExpression(node) => &mut node.span,
+            Node::UnaryExpression(node) => &mut node.span,
+            Node::Pipe(node) => &mut node.span,
+            Node::Compose(node) => &mut node.span,
+            Node::Range(node) => &mut node.span,
+            Node::FixityDeclaration(node) => &mut node.span,
+            Node::Call(node) => &mut node.span,
+            Node::Access(node) => &mut node.span,
+            Node::AccessIndex(node) => &mut node.span,
+            Node::TupleAny(node) => &mut node.span,
+            Node::TupleSymbols(node) => &mut node.span,
+            Node::TupleString(node) => &mut node.span,
+            Node::Atom(node) => &mut node.span,
+            Node::Unit(node) => &mut node.span,
+            Node::Error(node) => &mut node.span,
+        }
+    }
 }
 
 pub fn print_tree(node: Node) {
@@ -645,12 +1309,9 @@ fn print_tree_helper(node: Node, tabs: usize) {
             print!  ("{indent}lhs: ");
             print_tuple_single(node.symbols, tabs + 1);
 
-            match *node.ttype {
-                Some(node2) => {
-                    print!  ("{indent}type: ");
-                    print_tree_helper(node2, tabs + 1);
-                },
-                None => {}
+            if let Some(node2) = *node.ttype {
+                print!  ("{indent}type: ");
+                print_tree_helper(node2, tabs + 1);
             };
         
             print!("{indent}rhs: ");
@@ -667,38 +1328,36 @@ fn print_tree_helper(node: Node, tabs: usize) {
             println!("[fn anon]");
             print!  ("{indent}args: ");
             print_tuple_single(node.arguments, tabs + 1);
-            match *node.type_out {
-                Some(node2) => {
-                    print!  ("{}rtype: ", indent);
-                    print_tree_helper(node2, tabs + 1);
-                },
-                None => {},
+            if let Some(node2) = *node.type_out {
+                print!  ("{}rtype: ", indent);
+                print_tree_helper(node2, tabs + 1);
             };
             print!  ("{indent}value: ");
             print_tree_helper(*node.rhs, tabs + 1);
         },
         Node::FnSignature(node) => {
             println!("[fn signature]");
+            print_decorators(node.decorators, tabs);
             print!  ("{indent}name: ");
             print_tree_helper(*node.symbol, tabs + 1);
+            print_generics(node.generics, node.where_clause, tabs);
             print!  ("{indent}type: ");
             print_tree_helper(*node.ttype, tabs + 1);
         },
         Node::FnDeclaration(node) => {
             println!("[fn declaration]");
+            print_decorators(node.decorators, tabs);
             print!  ("{indent}name: ");
             print_tree_helper(*node.symbol, tabs + 1);
-            if node.arguments.len() > 0 {
+            print_generics(node.generics, node.where_clause, tabs);
+            if !node.arguments.is_empty() {
                 print!  ("{indent}args: ");
                 print_tuple_single(node.arguments, tabs + 1);
             }
 
-            match *node.type_out {
-                Some(node2) => {
-                    print!  ("{}rtype: ", indent);
-                    print_tree_helper(node2, tabs + 1);
-                }
-                None => {}
+            if let Some(node2) = *node.type_out {
+                print!  ("{}rtype: ", indent);
+                print_tree_helper(node2, tabs + 1);
             }
             print!  ("{indent}value: ");
             print_tree_helper(*node.rhs, tabs + 1);
@@ -709,12 +1368,9 @@ fn print_tree_helper(node: Node, tabs: usize) {
             print_tree_helper(*node.cond, tabs + 1);
             print!  ("{indent}true branch: ");
             print_tree_helper(*node.true_branch, tabs + 1);
-            match *node.false_branch {
-                Some(node2) => {
-                    print!  ("{indent}false branch: ");
-                    print_tree_helper(node2, tabs + 1);
-                }
-                None => {}
+            if let Some(node2) = *node.false_branch {
+                print!  ("{indent}false branch: ");
+                print_tree_helper(node2, tabs + 1);
             }
         },
         Node::PubExpr(node) => {
@@ -722,14 +1378,19 @@ fn print_tree_helper(node: Node, tabs: usize) {
             print!  ("{indent}rhs: ");
             print_tree_helper(*node.rhs, tabs + 1);
         },
+        Node::GenericParam(node) => {
+            println!("[generic]");
+            print!  ("{indent}name: ");
+            print_tree_helper(*node.symbol, tabs + 1);
+            println!("{indent}bounds: [tuple]");
+            print_tuple(node.bounds, tabs + 1);
+        },
         Node::DataDeclaration(node) => {
             println!("[data decleration]");
+            print_decorators(node.decorators, tabs);
             print!  ("{indent}name: ");
             print_tree_helper(*node.symbol, tabs + 1);
-            if node.generics.len() > 0 {
-                print!  ("{indent}generics: ");
-                print_tuple_single(node.generics, tabs + 1);
-            }
+            print_generics(node.generics, node.where_clause, tabs);
             println!("{indent}variants: [tuple]");
             print_tuple(node.variants, tabs + 1);
         },
@@ -740,6 +1401,24 @@ fn print_tree_helper(node: Node, tabs: usize) {
             print!  ("{indent}type: ");
             print_tree_helper(*node.ttype, tabs + 1);
         },
+        Node::TypeAlias(node) => {
+            println!("[type alias]");
+            print_decorators(node.decorators, tabs);
+            print!  ("{indent}name: ");
+            print_tree_helper(*node.symbol, tabs + 1);
+            print_generics(node.generics, Vec::new(), tabs);
+            print!  ("{indent}type: ");
+            print_tree_helper(*node.ttype, tabs + 1);
+        },
+        Node::Newtype(node) => {
+            println!("[newtype]");
+            print_decorators(node.decorators, tabs);
+            print!  ("{indent}name: ");
+            print_tree_helper(*node.symbol, tabs + 1);
+            print_generics(node.generics, Vec::new(), tabs);
+            print!  ("{indent}type: ");
+            print_tree_helper(*node.ttype, tabs + 1);
+        },
         Node::StructAnon(node) => {
             println!("[struct anon]");
             println!("{indent}fields: [tuple]");
@@ -747,8 +1426,10 @@ fn print_tree_helper(node: Node, tabs: usize) {
         },
         Node::StructDeclaration(node) => {
             println!("[struct declaration]");
+            print_decorators(node.decorators, tabs);
             print!  ("{indent}name: ");
             print_tree_helper(*node.symbol, tabs + 1);
+            print_generics(node.generics, node.where_clause, tabs);
             println!("{indent}fields: [tuple]");
             print_tuple(node.fields, tabs + 1);
         }, 
@@ -759,6 +1440,50 @@ fn print_tree_helper(node: Node, tabs: usize) {
             print!  ("{indent}type: ");
             print_tree_helper(*node.ttype, tabs + 1);
         },
+        Node::StructLiteral(node) => {
+            println!("[struct literal]");
+            print!  ("{indent}name: ");
+            print_tree_helper(*node.name, tabs + 1);
+            println!("{indent}fields: [tuple]");
+            print_tuple(node.fields, tabs + 1);
+        },
+        Node::StructUpdate(node) => {
+            println!("[struct update]");
+            print!  ("{indent}base: ");
+            print_tree_helper(*node.base, tabs + 1);
+            println!("{indent}fields: [tuple]");
+            print_tuple(node.fields, tabs + 1);
+        },
+        Node::FieldInit(node) => {
+            println!("[field]");
+            print!  ("{indent}name: ");
+            print_tree_helper(*node.symbol, tabs + 1);
+            if let Some(value) = *node.value {
+                print!  ("{indent}value: ");
+                print_tree_helper(value, tabs + 1);
+            }
+        },
+        Node::TraitDeclaration(node) => {
+            println!("[trait declaration]");
+            print_decorators(node.decorators, tabs);
+            print!  ("{indent}name: ");
+            print_tree_helper(*node.symbol, tabs + 1);
+            print_generics(node.generics, node.where_clause, tabs);
+            println!("{indent}methods: [tuple]");
+            print_tuple(node.methods, tabs + 1);
+        },
+        Node::ImplBlock(node) => {
+            println!("[impl]");
+            print_decorators(node.decorators, tabs);
+            if let Some(node2) = *node.ttrait {
+                print!  ("{indent}trait: ");
+                print_tree_helper(node2, tabs + 1);
+            };
+            print!  ("{indent}type: ");
+            print_tree_helper(*node.ttype, tabs + 1);
+            println!("{indent}items: [tuple]");
+            print_tuple(node.items, tabs + 1);
+        },
         Node::Package(node) => {
             println!("[package]");
             print!  ("{indent}name: ");
@@ -768,6 +1493,18 @@ fn print_tree_helper(node: Node, tabs: usize) {
             println!("[import]");
             print!  ("{indent}name: ");
             print_tuple_single(node.rhs, tabs + 1);
+            if let Some(alias) = *node.alias {
+                print!  ("{indent}alias: ");
+                print_tree_helper(alias, tabs + 1);
+            }
+            if !node.items.is_empty() {
+                print!  ("{indent}items: ");
+                print_tuple_single(node.items, tabs + 1);
+            }
+            if !node.hiding.is_empty() {
+                print!  ("{indent}hiding: ");
+                print_tuple_single(node.hiding, tabs + 1);
+            }
         },
         Node::MatchExpression(node) => {
             println!("[match]");
@@ -780,6 +1517,10 @@ fn print_tree_helper(node: Node, tabs: usize) {
             println!("[match branch]");
             print!  ("{indent}lhs: ");
             print_tree_helper(*node.lhs, tabs + 1);
+            if let Some(guard) = *node.guard {
+                print!  ("{indent}guard: ");
+                print_tree_helper(guard, tabs + 1);
+            }
             print!  ("{indent}rhs: ");
             print_tree_helper(*node.rhs, tabs + 1);
         },
@@ -794,6 +1535,56 @@ fn print_tree_helper(node: Node, tabs: usize) {
             print!  ("{}tail: ", indent);
             print_tree_helper(*node.tail, tabs + 1);  
         },
+        Node::ListComprehension(node) => {
+            println!("[list comprehension]");
+            print!  ("{indent}expr: ");
+            print_tree_helper(*node.expr, tabs + 1);
+            println!("{indent}clauses: [tuple]");
+            print_tuple(node.clauses, tabs + 1);
+        },
+        Node::Generator(node) => {
+            println!("[generator]");
+            print!  ("{indent}pattern: ");
+            print_tree_helper(*node.pattern, tabs + 1);
+            print!  ("{indent}iter: ");
+            print_tree_helper(*node.iter, tabs + 1);
+        },
+        Node::WildcardPattern(_) => println!("[wildcard]"),
+        Node::ConstructorPattern(node) => {
+            println!("[constructor]");
+            print!  ("{indent}name: ");
+            print_tree_helper(*node.name, tabs + 1);
+            if !node.args.is_empty() {
+                print!  ("{indent}args: ");
+                print_tuple_single(node.args, tabs + 1);
+            }
+        },
+        Node::TuplePattern(node) => {
+            println!("[tuple pattern]");
+            print_tuple(node.items, tabs);
+        },
+        Node::ListPattern(node) => {
+            println!("[list pattern]");
+            if !node.items.is_empty() {
+                println!("{indent}items: [tuple]");
+                print_tuple(node.items, tabs + 1);
+            }
+            if let Some(rest) = *node.rest {
+                print!  ("{indent}rest: ");
+                print_tree_helper(rest, tabs + 1);
+            }
+        },
+        Node::AsPattern(node) => {
+            println!("[as]");
+            print!  ("{indent}pattern: ");
+            print_tree_helper(*node.pattern, tabs + 1);
+            print!  ("{indent}name: ");
+            print_tree_helper(*node.symbol, tabs + 1);
+        },
+        Node::OrPattern(node) => {
+            println!("[or pattern]");
+            print_tuple(node.patterns, tabs);
+        },
         Node::TypeFn(node) => {
             println!("[{}]", node.token.typ);
             print!  ("{indent}lhs: ");
@@ -830,6 +1621,15 @@ fn print_tree_helper(node: Node, tabs: usize) {
             println!("{indent}stmts: [block]");
             print_tuple(node.statements, tabs + 1);
         },
+        Node::ForExpression(node) => {
+            println!("[for]");
+            print!  ("{indent}binder: ");
+            print_tuple_single(node.binder, tabs + 1);
+            print!  ("{indent}iter: ");
+            print_tree_helper(*node.iter, tabs + 1);
+            println!("{indent}stmts: [block]");
+            print_tuple(node.statements, tabs + 1);
+        },
         Node::DoExpression(node) => {
             println!("[do]");
             println!("{indent}stmts: [block]");
@@ -852,11 +1652,39 @@ fn print_tree_helper(node: Node, tabs: usize) {
             print!  ("{indent}rhs: ");
             print_tree_helper(*node.rhs, tabs + 1);
         },
+        Node::Pipe(node) => {
+            println!("[pipe]");
+            print!  ("{indent}value: ");
+            print_tree_helper(*node.lhs, tabs + 1);
+            print!  ("{indent}into: ");
+            print_tree_helper(*node.rhs, tabs + 1);
+        },
+        Node::Compose(node) => {
+            println!("[compose {}]", node.token.typ);
+            print!  ("{indent}lhs: ");
+            print_tree_helper(*node.lhs, tabs + 1);
+            print!  ("{indent}rhs: ");
+            print_tree_helper(*node.rhs, tabs + 1);
+        },
+        Node::Range(node) => {
+            println!("[range{}]", if node.inclusive {" inclusive"} else {""});
+            print!  ("{indent}lhs: ");
+            print_tree_helper(*node.lhs, tabs + 1);
+            print!  ("{indent}rhs: ");
+            print_tree_helper(*node.rhs, tabs + 1);
+        },
+        Node::FixityDeclaration(node) => {
+            println!("[{}]", node.token.typ);
+            print!  ("{indent}level: ");
+            print_tree_helper(*node.level, tabs + 1);
+            print!  ("{indent}operators: ");
+            print_tuple_single(node.operators, tabs + 1);
+        },
         Node::Call(node) => {
             println!("[call]");
             print!  ("{indent}lhs: ");
             print_tree_helper(*node.lhs, tabs + 1);
-            if node.args.len() > 0 {
+            if !node.args.is_empty() {
                 print!  ("{}arg: ", indent);
                 print_tuple_single(node.args, tabs + 1);
             }
@@ -877,26 +1705,32 @@ fn print_tree_helper(node: Node, tabs: usize) {
         },
         Node::TupleAny(node) => {
             println!("[tuple]");
-            if node.items.len() > 0 {
+            if !node.items.is_empty() {
                 print_tuple(node.items, tabs);
             }
         }
         Node::Atom(node) => {
             let tok = match node.token.typ {
                 TokenType::Symbol(atom)  => format!("Symbol '{}'", atom),
+                TokenType::Operator(atom) => format!("Operator '{}'", atom),
                 TokenType::Integer(atom) => format!("Int '{}'", atom),
+                TokenType::Float(atom)   => format!("Float '{}'", atom),
                 TokenType::String(atom)  => format!("String \"{}\"", atom),
-                _ => format!(""),
+                TokenType::Bool(atom)    => format!("Bool '{}'", atom),
+                TokenType::None          => "None".to_string(),
+                _ => String::new(),
             };
             println!("[{}]", tok);
         },
+        Node::Unit(_) => println!("[unit]"),
+        Node::Error(_) => println!("[error]"),
         Node::FnArgTyped(node) => {
             match *node.ttype {
-                None => { print_tree_helper(*node.symbol, tabs); },
+                None => { print_tree_helper(*node.pattern, tabs); },
                 Some(ttype) => {
                     println!("[fn argument]");
-                    print!  ("{indent}symbol: ");
-                    print_tree_helper(*node.symbol, tabs + 1);
+                    print!  ("{indent}pattern: ");
+                    print_tree_helper(*node.pattern, tabs + 1);
                     print!  ("{indent}type: ");
                     print_tree_helper(ttype, tabs + 1);
                 },
@@ -906,27 +1740,50 @@ fn print_tree_helper(node: Node, tabs: usize) {
     }
 }
 
+fn print_decorators(decorators: Vec<Decorator>, tabs: usize) {
+    let indent = TAB.repeat(tabs);
+    if decorators.is_empty() {
+        return;
+    }
+    println!("{indent}decorators: [tuple]");
+    for (idx, decorator) in decorators.into_iter().enumerate() {
+        println!("{indent}{TAB}{idx}: [decorator '{}']", decorator.name);
+        if !decorator.args.is_empty() {
+            print!  ("{indent}{TAB}{TAB}args: ");
+            print_tuple_single(decorator.args, tabs + 3);
+        }
+    }
+}
+
+fn print_generics(generics: Vec<Node>, where_clause: Vec<Node>, tabs: usize) {
+    let indent = TAB.repeat(tabs);
+    if !generics.is_empty() {
+        print!  ("{indent}generics: ");
+        print_tuple_single(generics, tabs + 1);
+    }
+    if !where_clause.is_empty() {
+        print!  ("{indent}where: ");
+        print_tuple_single(where_clause, tabs + 1);
+    }
+}
+
 fn print_tuple(nodes: Vec<Node>, tabs: usize) {
     let indent = TAB.repeat(tabs);
-    let mut idx = 0;
-    for branch in nodes {
+    for (idx, branch) in nodes.into_iter().enumerate() {
         print!("{indent}{idx}: ");
-        idx += 1;
         print_tree_helper(branch, tabs + 1);
     }
 }
 
 fn print_tuple_single(nodes: Vec<Node>, tabs: usize) {
     let indent = TAB.repeat(tabs);
-    if nodes.len() == 0 {
+    if nodes.is_empty() {
         println!("()");
     }
     if nodes.len() > 1 {
-        let mut idx = 0;
         println!("[tuple]");
-        for arg in nodes {
+        for (idx, arg) in nodes.into_iter().enumerate() {
             print!("{indent}{idx}: ");
-            idx += 1;
             print_tree_helper(arg, tabs + 1);
         }
     } else {
@@ -1,2 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod parser;
-pub mod ast;
\ No newline at end of file
+pub mod ast;
+pub mod operators;
\ No newline at end of file
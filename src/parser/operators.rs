@@ -0,0 +1,146 @@
+use crate::tokens::tokens::TokenType;
+
+// Binding powers for the built-in operators. They are spaced out by ten so
+// new operators can be slotted in between two existing levels, and so that
+// fixity level `n` lines up with binding power `(n + 1) * 10` (see `fixity_bp`).
+pub const BP_PIPE: u8       = 20;   /* |>                   */
+pub const BP_RANGE: u8      = 24;   /* .. ..=               */
+pub const BP_COMPOSE: u8    = 26;   /* >> <<                */
+pub const BP_OR: u8         = 30;   /* ||                   */
+pub const BP_AND: u8        = 40;   /* &&                   */
+pub const BP_EQUALITY: u8   = 50;   /* == !=                */
+pub const BP_COMPARISON: u8 = 55;   /* > >= < <=            */
+pub const BP_BITOR: u8      = 60;   /* |                    */
+pub const BP_BITXOR: u8     = 62;   /* ^                    */
+pub const BP_BITAND: u8     = 64;   /* &                    */
+pub const BP_SHIFT: u8      = 66;   /* <<< >>>              */
+pub const BP_ADDITION: u8   = 70;   /* + -                  */
+pub const BP_MULT: u8       = 80;   /* * / %                */
+pub const BP_UNARY: u8      = 85;   /* ! - ~ (prefix)       */
+pub const BP_EXPONENT: u8   = 90;   /* **                   */
+pub const BP_POSTFIX: u8    = 120;  /* f(x) xs[i] a.b P{..} */
+
+/// Associativity of an infix operator, deciding how `a op b op c` groups.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Assoc {
+    Left,   /* (a op b) op c */
+    Right,  /* a op (b op c) */
+    None,   /* a op b op c is an error */
+}
+
+/// Handler names the routine the parser runs once it has matched an
+/// operator, and therefore which node gets built.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Handler {
+    Unary,  /* prefix:  op rhs          */
+    Binary, /* infix:   lhs op rhs      */
+    Pipe,   /* infix:   lhs '|>' rhs    */
+    Compose,/* infix:   lhs '>>' rhs    */
+    Range,  /* infix:   lhs '..' rhs    */
+    Call,   /* postfix: lhs '(' ... ')' */
+    Index,  /* postfix: lhs '[' ... ']' */
+    Access, /* postfix: lhs '.' symbol  */
+    Struct, /* postfix: lhs '{' ... '}' */
+}
+
+/// Operator holds everything the Pratt parser needs to know about a token
+/// in a given position.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Operator {
+    pub bp: u8,
+    pub assoc: Assoc,
+    pub handler: Handler,
+}
+
+/// OperatorTable maps tokens to their prefix, infix and postfix operators.
+pub struct OperatorTable {
+    prefix: Vec<(TokenType, Operator)>,
+    infix: Vec<(TokenType, Operator)>,
+    postfix: Vec<(TokenType, Operator)>,
+}
+
+impl Default for OperatorTable {
+    fn default() -> OperatorTable {
+        let mut table = OperatorTable { prefix: vec![], infix: vec![], postfix: vec![] };
+
+        table.add_prefix(TokenType::Not, BP_UNARY, Handler::Unary);
+        table.add_prefix(TokenType::Minus, BP_UNARY, Handler::Unary);
+        table.add_prefix(TokenType::Tilde, BP_UNARY, Handler::Unary);
+
+        table.add_infix(TokenType::Pipe, BP_PIPE, Assoc::Left, Handler::Pipe);
+        table.add_infix(TokenType::DotDot, BP_RANGE, Assoc::None, Handler::Range);
+        table.add_infix(TokenType::DotDotEq, BP_RANGE, Assoc::None, Handler::Range);
+        table.add_infix(TokenType::ComposeRight, BP_COMPOSE, Assoc::Left, Handler::Compose);
+        table.add_infix(TokenType::ComposeLeft, BP_COMPOSE, Assoc::Left, Handler::Compose);
+        table.add_infix(TokenType::Or, BP_OR, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::And, BP_AND, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Eq, BP_EQUALITY, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::NotEq, BP_EQUALITY, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Greater, BP_COMPARISON, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::GreaterEqual, BP_COMPARISON, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Less, BP_COMPARISON, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::LessEqual, BP_COMPARISON, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Bar, BP_BITOR, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Caret, BP_BITXOR, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Ampersand, BP_BITAND, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::ShiftLeft, BP_SHIFT, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::ShiftRight, BP_SHIFT, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Plus, BP_ADDITION, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Minus, BP_ADDITION, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Star, BP_MULT, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Slash, BP_MULT, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::Percent, BP_MULT, Assoc::Left, Handler::Binary);
+        table.add_infix(TokenType::DoubleStar, BP_EXPONENT, Assoc::Right, Handler::Binary);
+
+        table.add_postfix(TokenType::LParen, BP_POSTFIX, Handler::Call);
+        table.add_postfix(TokenType::LBracket, BP_POSTFIX, Handler::Index);
+        table.add_postfix(TokenType::Dot, BP_POSTFIX, Handler::Access);
+        table.add_postfix(TokenType::LCurly, BP_POSTFIX, Handler::Struct);
+
+        table
+    }
+}
+
+impl OperatorTable {
+    pub fn add_prefix(&mut self, typ: TokenType, bp: u8, handler: Handler) {
+        self.prefix.retain(|(t, _)| *t != typ);
+        self.prefix.push((typ, Operator { bp, assoc: Assoc::Right, handler }));
+    }
+
+    pub fn add_infix(&mut self, typ: TokenType, bp: u8, assoc: Assoc, handler: Handler) {
+        self.infix.retain(|(t, _)| *t != typ);
+        self.infix.push((typ, Operator { bp, assoc, handler }));
+    }
+
+    pub fn add_postfix(&mut self, typ: TokenType, bp: u8, handler: Handler) {
+        self.postfix.retain(|(t, _)| *t != typ);
+        self.postfix.push((typ, Operator { bp, assoc: Assoc::Left, handler }));
+    }
+
+    /// Returns the operator `typ` stands for at the start of an operand.
+    pub fn prefix(&self, typ: &TokenType) -> Option<Operator> {
+        find(&self.prefix, typ)
+    }
+
+    /// Returns the operator `typ` stands for between two operands. 
+    /// User-defined operators only have one once they have been given a 
+    /// fixity declaration.
+    pub fn infix(&self, typ: &TokenType) -> Option<Operator> {
+        find(&self.infix, typ)
+    }
+
+    /// Returns the operator `typ` stands for after an operand.
+    pub fn postfix(&self, typ: &TokenType) -> Option<Operator> {
+        find(&self.postfix, typ)
+    }
+}
+
+/// Maps a fixity level (0 to 9, as written in `infixl 6 <+>`) to a binding
+/// power.
+pub fn fixity_bp(level: i64) -> u8 {
+    ((level + 1) * 10) as u8
+}
+
+fn find(ops: &[(TokenType, Operator)], typ: &TokenType) -> Option<Operator> {
+    ops.iter().find(|(t, _)| t == typ).map(|(_, op)| *op)
+}
@@ -1,8 +1,10 @@
 
 use crate::parser::ast::*;
+use crate::parser::operators::*;
 use crate::consume_token;
 use crate::errors::errors::*;
 use crate::tokens::tokens::*;
+use crate::tokens::lexer::{lex, operator_token};
 
 /// Parser encapsulates the recursive-descent parser for ivy.
 #[derive(Default)]
@@ -10,26 +12,261 @@ pub struct Parser {
     tokens: Vec<Token>,
     last:   Option<(usize, usize)>,
     cursor: usize,
+    operators: OperatorTable,
+    errors: Vec<ParserError>,
+    restrictions: Restrictions,
+    options: ParserOptions,
+    depth: usize,
 }
 
-pub fn parse(tokens: Vec<Token>) -> Result<Node, ParserError>{
+/// The default for `ParserOptions::max_depth`. It fits in the 8 MiB main
+/// thread stack even in debug builds; lower it when parsing on a thread with
+/// a smaller stack.
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// ParserOptions switches between the dialects the parser accepts. The 
+/// default is the strict dialect, where every statement ends with a `;`.
+#[derive(Clone, Copy)]
+pub struct ParserOptions {
+    /// Automatic semicolon insertion: a line break ends a statement that is
+    /// complete, so its `;` can be left out.
+    pub asi: bool,
+    /// How deeply expressions, patterns and types may nest before parsing 
+    /// fails with a "nesting too deep" error rather than overflowing the
+    /// stack. Each bracket or prefix operator opens about two levels, and
+    /// so does an infix operator while its right operand is being parsed.
+    pub max_depth: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> ParserOptions {
+        ParserOptions { asi: false, max_depth: DEFAULT_MAX_DEPTH }
+    }
+}
+
+/// Restrictions on what an expression may contain, for places where an 
+/// operator would be ambiguous with the surrounding syntax. They are lifted
+/// again inside brackets.
+#[derive(Default, Clone, Copy)]
+struct Restrictions {
+    no_bar: bool,            /* `|` ends the expression, as in a match branch */
+    no_struct_literal: bool, /* `{` starts a block, as after a while condition */
+    ignore_newlines: bool,   /* a line break never ends the expression, as in brackets */
+}
+
+/// An infix operator whose right operand is still being parsed, together
+/// with its left operand and where that started.
+struct PendingOp {
+    start: Span,
+    lhs: Node,
+    tok: Token,
+    op: Operator,
+}
+
+impl PendingOp {
+    /// Returns the binding power the right operand is parsed with. It is one
+    /// lower for right associative operators, so that `a ** b ** c` nests to
+    /// the right.
+    fn rhs_bp(&self) -> u8 {
+        match self.op.assoc {
+            Assoc::Right => self.op.bp - 1,
+            Assoc::Left | Assoc::None => self.op.bp,
+        }
+    }
+}
+
+/// Parses `tokens` into a tree. Parsing doesn't stop at the first error: 
+/// every error found is returned alongside a best-effort tree, in which 
+/// statements that failed to parse are replaced by `Error` nodes.
+pub fn parse(tokens: Vec<Token>) -> (Node, Vec<ParserError>) {
+    parse_with(tokens, ParserOptions::default())
+}
+
+/// Parses `tokens` like `parse`, in the dialect selected by `options`.
+pub fn parse_with(tokens: Vec<Token>, options: ParserOptions) -> (Node, Vec<ParserError>) {
+    let mut p = Parser::new(tokens);
+    p.options = options;
+    let root = p.parse();
+    (root, p.errors)
+}
+
+/// Lexes and parses `src` as a single expression.
+pub fn parse_expr_str(src: &str) -> Result<Node, SyntaxError> {
+    parse_fragment(src, Parser::parse_expression)
+}
+
+/// Lexes and parses `src` as a single type, such as `Int -> [a]`.
+pub fn parse_type_str(src: &str) -> Result<Node, SyntaxError> {
+    parse_fragment(src, Parser::parse_type_fn)
+}
+
+/// Lexes and parses `src` as a single statement, including its `;`.
+pub fn parse_statement_str(src: &str) -> Result<Node, SyntaxError> {
+    parse_fragment(src, Parser::parse_statement)
+}
+
+/// Lexes and parses `src` as a single pattern, such as `Some((x, _))`.
+pub fn parse_pattern_str(src: &str) -> Result<Node, SyntaxError> {
+    parse_fragment(src, Parser::parse_pattern)
+}
+
+/// Lexes `src` and runs `parse` on its tokens. Unlike `parse`, there is no
+/// error recovery: the first error is returned, even one the parser recovered
+/// from inside a block, and so is an error for any input left over once 
+/// `parse` is done.
+fn parse_fragment<F>(src: &str, parse: F) -> Result<Node, SyntaxError>
+where F: FnOnce(&mut Parser) -> Result<Node, ParserError> {
+    let tokens = lex(src).map_err(SyntaxError::Lexer)?;
     let mut p = Parser::new(tokens);
-    p.parse()
+    let result = parse(&mut p);
+    if !p.errors.is_empty() {
+        return Err(SyntaxError::Parser(p.errors.remove(0)));
+    }
+    let node = result.map_err(SyntaxError::Parser)?;
+    match p.peek() {
+        Some(tok) => Err(SyntaxError::Parser(
+            new_parser_expected(tok.row, tok.col, "end of input".to_string())
+        )),
+        None => Ok(node),
+    }
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Parser {
-        Parser { tokens, cursor: 0 , last: None}
+        Parser { 
+            tokens, cursor: 0 , last: None, 
+            operators: OperatorTable::default(), errors: Vec::new(),
+            restrictions: Restrictions::default(),
+            options: ParserOptions::default(),
+            depth: 0,
+        }
     }
 
-    fn parse(&mut self) -> Result<Node, ParserError> {
+    fn parse(&mut self) -> Node {
+        let start = self.span_start();
         let mut nodes = Vec::new();
 
         while !self.is_done() {
-            nodes.push( self.parse_statement()? );
+            nodes.push( self.recover(None, Parser::parse_statement) );
+        }
+
+        self.finish(start, NewRootNode(nodes))
+    }
+
+    /// Runs `parse` and returns its node. If it fails, the error is recorded
+    /// and the parser synchronizes on the next statement boundary, returning
+    /// an `Error` node covering the skipped tokens instead.
+    fn recover<F>(&mut self, closer: Option<TokenType>, parse: F) -> Node 
+    where F: FnOnce(&mut Parser) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let cursor = self.cursor;
+        let restrictions = self.restrictions;
+        let depth = self.depth;
+        match parse(self) {
+            Ok(node) => node,
+            Err(err) => {
+                self.errors.push(err);
+                self.restrictions = restrictions;
+                self.depth = depth;
+                self.synchronize(closer, cursor);
+                self.finish(start, NewError())
+            }
+        }
+    }
+
+    /// Runs `parse` with `restrictions` in place of the current ones, which 
+    /// are restored afterwards.
+    fn restricted<T, F>(&mut self, restrictions: Restrictions, parse: F) -> Result<T, ParserError>
+    where F: FnOnce(&mut Parser) -> Result<T, ParserError> {
+        let prev = self.restrictions;
+        self.restrictions = restrictions;
+        let res = parse(self);
+        self.restrictions = prev;
+        res
+    }
+
+    /// Runs `parse` one nesting level deeper. Every rule that can end up 
+    /// calling itself goes through here, so that input nested more than 
+    /// `max_depth` levels deep fails with an error instead of overflowing the
+    /// stack. Levels opened by `parse` itself are closed again afterwards.
+    fn nested<F>(&mut self, parse: F) -> Result<Node, ParserError>
+    where F: FnOnce(&mut Parser) -> Result<Node, ParserError> {
+        let depth = self.depth;
+        self.descend()?;
+        let res = parse(self);
+        self.depth = depth;
+        res
+    }
+
+    /// Opens one more level of nesting, failing once `max_depth` levels are
+    /// open.
+    fn descend(&mut self) -> Result<(), ParserError> {
+        if self.depth >= self.options.max_depth {
+            let (r, c) = match self.peek() {
+                Some(tok) => (tok.row, tok.col),
+                None => self.get_last_token_location(),
+            };
+            return Err(new_parser_nesting_too_deep(r, c, self.options.max_depth));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Parses an expression inside brackets, where no restrictions apply and
+    /// line breaks are insignificant.
+    fn parse_delimited(&mut self) -> Result<Node, ParserError> {
+        self.restricted(Restrictions { ignore_newlines: true, ..Restrictions::default() }, 
+            Parser::parse_expression)
+    }
+
+    /// Skips tokens until a new statement can start: just past a `;`, or 
+    /// at a declaration keyword, `closer` or, in ASI mode, the start of a 
+    /// line. Brackets opened while skipping are skipped as a whole, and so 
+    /// is the rest of any bracket the error occurred in. In there, `let`, 
+    /// `fn` and `struct` start expressions rather than statements, but the
+    /// other declaration keywords still end the skipping, since the bracket
+    /// must have been left open. Stray closing brackets are skipped. The 
+    /// parser always moves past the token at `cursor`, where the failed 
+    /// statement started, so recovery can't get stuck on it.
+    fn synchronize(&mut self, closer: Option<TokenType>, cursor: usize) {
+        let mut enclosing = self.open_brackets(cursor);
+        let mut nested = 0;
+        while let Some(tok) = self.peek() {
+            let moved = self.cursor > cursor;
+            match tok.typ {
+                TokenType::LParen | TokenType::LBracket | TokenType::LCurly => nested += 1,
+                TokenType::RParen | TokenType::RBracket 
+                | TokenType::RCurly if nested > 0 => nested -= 1,
+                TokenType::RParen | TokenType::RBracket 
+                | TokenType::RCurly if enclosing > 0 => enclosing -= 1,
+                TokenType::Semicolon if nested == 0 => {
+                    self.next();
+                    return;
+                },
+                _ if nested > 0 || !moved => {},
+                ref typ if enclosing == 0 && closer.as_ref() == Some(typ) => return,
+                ref typ if is_declaration(typ) && (enclosing == 0 || !starts_expression(typ)) => return,
+                _ if enclosing == 0 && self.options.asi && tok.newline => return,
+                _ => {},
+            };
+            self.next();
+        }
+    }
+
+    /// Returns the number of brackets opened, but not yet closed, between 
+    /// `cursor` and the next token.
+    fn open_brackets(&self, cursor: usize) -> usize {
+        let mut depth: usize = 0;
+        for tok in &self.tokens[cursor..self.cursor] {
+            match tok.typ {
+                TokenType::LParen | TokenType::LBracket | TokenType::LCurly => depth += 1,
+                TokenType::RParen | TokenType::RBracket 
+                | TokenType::RCurly => depth = depth.saturating_sub(1),
+                _ => {},
+            };
         }
 
-        Ok(NewRootNode(nodes))
+        depth
     }
 
     /// Parses a statement based on the following rule:
@@ -38,11 +275,42 @@ impl Parser {
     /// 
     fn parse_statement(&mut self) -> Result<Node, ParserError> {
         let expr = self.parse_expression()?;
-        consume_token!(self, TokenType::Semicolon)?;
+        self.end_complete_statement()?;
 
         Ok(expr)
     }
 
+    /// Consumes the `;` ending a statement which has otherwise been parsed.
+    /// If the `;` is missing but the next token looks like the start of the
+    /// next statement, because it's on a new line or is a declaration 
+    /// keyword, the error is recorded without failing the statement, which 
+    /// keeps it in the tree.
+    fn end_complete_statement(&mut self) -> Result<(), ParserError> {
+        match self.end_statement() {
+            Err(err) if self.peek().is_some_and(|tok| tok.newline || is_declaration(&tok.typ)) => {
+                self.errors.push(err);
+                Ok(())
+            },
+            res => res,
+        }
+    }
+
+    /// Consumes the `;` ending a statement. In ASI mode the `;` is optional
+    /// before a line break, a closing bracket or the end of the input.
+    fn end_statement(&mut self) -> Result<(), ParserError> {
+        if self.options.asi {
+            match self.peek() {
+                None => return Ok(()),
+                Some(tok) if tok.newline => return Ok(()),
+                Some(tok) if matches!(tok.typ, TokenType::RCurly | TokenType::RParen) => return Ok(()),
+                _ => {},
+            };
+        }
+        consume_token!(self, TokenType::Semicolon)?;
+
+        Ok(())
+    }
+
     /// Parses an expression based on the following rule:
     /// 
     /// <expression> ::= <letExpr>      
@@ -51,49 +319,65 @@ impl Parser {
     ///                | <ifExpr>       
     ///                | <pubExpr>      
     ///                | <dataExpr>     
+    ///                | <typeExpr>     
     ///                | <structStmt>   
+    ///                | <traitExpr>    
+    ///                | <implExpr>     
     ///                | <packageStmt>  
     ///                | <importStmt>   
     ///                | <matchExpr>    
     ///                | <whileExpr>    
+    ///                | <forExpr>      
     ///                | <doExpr>       
     ///                | <returnExpr>   
+    ///                | <decorated>    
+    ///                | <fixityDecl>   
     ///                | <or>           
     ///                | <tupleAny> ;   
     /// 
     fn parse_expression(&mut self) -> Result<Node, ParserError> {
-        match self.peek() {
-            Some(tok) => {
-                match tok.typ {
-                    TokenType::Let      => self.parse_let(),
-                    TokenType::Mut      => self.parse_mut(),
-                    TokenType::Fn       => self.parse_function(),
-                    TokenType::If       => self.parse_if(),
-                    TokenType::Pub      => self.parse_pub(),
-                    TokenType::Data     => self.parse_data(),
-                    TokenType::Struct   => self.parse_struct(),
-                    TokenType::Package  => self.parse_package(),
-                    TokenType::Import   => self.parse_import(),
-                    TokenType::Match    => self.parse_match(),
-                    TokenType::While    => self.parse_while(),
-                    TokenType::Do       => self.parse_do(),
-                    TokenType::Return   => self.parse_return(),
-                    _                   => self.parse_or(),
+        self.nested(|p| {
+            match p.peek() {
+                Some(tok) => {
+                    match tok.typ {
+                        TokenType::Let      => p.parse_let(),
+                        TokenType::Mut      => p.parse_mut(),
+                        TokenType::Fn       => p.parse_function(),
+                        TokenType::If       => p.parse_if(),
+                        TokenType::Pub      => p.parse_pub(),
+                        TokenType::Data     => p.parse_data(),
+                        TokenType::Type     => p.parse_type_alias(),
+                        TokenType::Newtype  => p.parse_newtype(),
+                        TokenType::Struct   => p.parse_struct(),
+                        TokenType::Trait    => p.parse_trait(),
+                        TokenType::Impl     => p.parse_impl(),
+                        TokenType::Package  => p.parse_package(),
+                        TokenType::Import   => p.parse_import(),
+                        TokenType::Match    => p.parse_match(),
+                        TokenType::While    => p.parse_while(),
+                        TokenType::For      => p.parse_for(),
+                        TokenType::Do       => p.parse_do(),
+                        TokenType::Return   => p.parse_return(),
+                        TokenType::Decorator(_) => p.parse_decorated(Parser::parse_expression),
+                        TokenType::Infixl | TokenType::Infixr
+                        | TokenType::Infix  => p.parse_fixity(),
+                        _                   => p.parse_or(),
+                    }
+                }
+                _ => {
+                    let (r, c) = p.get_last_token_location();
+                    Err(new_parser_expected(r, c + 1, "<expression>".to_string()))
                 }
             }
-            _ => {
-                let (r, c) = self.get_last_token_location();
-                Err(new_parser_expected(r, c + 1, format!("<expression>")))
-            }
-        }
+        })
     }
 
     /// Parses a let expression based on the following pattern:
     /// 
-    /// <letExpr> ::= 'let' [ 'mut' ]? [ <symbol> | <tupleSymbols> ] 
-    ///                                [ '::' <typeFn> ]? '=' <expression>;
+    /// <letExpr> ::= 'let' [ 'mut' ]? <binder> [ '::' <typeFn> ]? '=' <expression>;
     /// 
     fn parse_let(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::Let)?;
         let is_mut = if let Some(tok) = self.peek() {
             if tok.typ == TokenType::Mut {
@@ -101,6 +385,35 @@ impl Parser {
                 true
             } else { false }
         } else { false };
+        let symbols = self.parse_binder()?;
+
+        let ttype = if let Some(tok2) = self.peek() {
+            if tok2.typ == TokenType::DoubleColon {
+                consume_token!(self, TokenType::DoubleColon)?;
+                Some(self.parse_type_fn()?)
+            } else {
+                None
+            }
+        } else { None };
+
+        consume_token!(self, TokenType::Bind)?;
+
+        let rhs = self.parse_expression()?;
+
+        if is_mut {
+            Ok(self.finish(start, NewLetMutExpr(token, symbols, rhs, ttype)))
+        } else {
+            Ok(self.finish(start, NewLetExpr(token, symbols, rhs, ttype)))
+        }
+    }
+
+    /// Parses the names bound by a let expression or for loop based on the
+    /// following rule:
+    /// 
+    /// <binder> ::= <symbol> | <tupleSymbols> ;
+    /// 
+    /// Returns a vector of nodes rather than a single node.
+    fn parse_binder(&mut self) -> Result<Vec<Node>, ParserError> {
         let mut symbols = Vec::new();
         if let Some(tok) = self.peek() {
             match tok.typ {
@@ -122,28 +435,15 @@ impl Parser {
                 TokenType::Symbol(_) => {
                     symbols.push(self.parse_symbol()?);
                 },
-                _ => {}
+                _ => {
+                    return Err(new_parser_expected_one_of(tok.row, tok.col, vec![
+                        format!("<symbol>"), format!("`(`")
+                    ]))
+                }
             };
         };
 
-        let ttype = if let Some(tok2) = self.peek() {
-            if tok2.typ == TokenType::DoubleColon {
-                consume_token!(self, TokenType::DoubleColon)?;
-                Some(self.parse_type_fn()?)
-            } else {
-                None
-            }
-        } else { None };
-
-        consume_token!(self, TokenType::Bind)?;
-
-        let rhs = self.parse_expression()?;
-
-        if is_mut {
-            Ok(NewLetMutExpr(token, symbols, rhs, ttype))
-        } else {
-            Ok(NewLetExpr(token, symbols, rhs, ttype))
-        }
+        Ok(symbols)
     }
 
     /// Parses a mut expression based on the following pattern:
@@ -151,12 +451,13 @@ impl Parser {
     /// <mutExpr> ::= 'mut' [ <symbol> | <access> ] '=' <expression> ;
     /// 
     fn parse_mut(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::Mut)?;
-        let lhs = self.parse_access_attr()?;
+        let lhs = self.parse_expr_bp(BP_POSTFIX - 1)?;
         consume_token!(self, TokenType::Bind)?;
         let rhs = self.parse_expression()?;
         
-        Ok(NewMutExpr(token, lhs, rhs))
+        Ok(self.finish(start, NewMutExpr(token, lhs, rhs)))
     }
 
     /// Parses a function expression/declaration based on the folllwing pattern:
@@ -167,28 +468,7 @@ impl Parser {
         if let Some(tok) = self.peek_twice() {
             match tok.typ {
                 TokenType::LParen => self.parse_fn_anon(),
-                TokenType::Symbol(_) => {
-                    if let Some(tok2) = self.peek_thrice() {
-                        match tok2.typ {
-                            TokenType::DoubleColon => self.parse_fn_signature(),
-                            TokenType::LParen => self.parse_fn_declaration(),
-                            _ => {
-                                self.next();
-                                self.next();
-                                Err(new_parser_expected_one_of(tok2.row, tok2.col, vec!(
-                                    format!("`::`"), format!("`(`"))
-                                ))
-                            }
-                        }
-                    } else {
-                        self.next();
-                        self.next();
-                        let (r, c) = self.get_last_token_location();
-                        Err(new_parser_expected_one_of(r, c + 1, vec!(
-                            format!("`::`"), format!("`(`"))
-                        ))
-                    }
-                },
+                TokenType::Symbol(_) => self.parse_fn_named(),
                 _ => Err(new_parser_expected_one_of(tok.row, tok.col, vec![
                     format!("<symbol>"), format!("`(`")
                 ]))
@@ -208,6 +488,7 @@ impl Parser {
     /// <fnAnon> ::= 'fn' <fnArgs> [ ':' <typeFn> ]? '=>' <expression> ;
     /// 
     fn parse_fn_anon(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::Fn)?;
         let arguments = self.parse_fn_args()?;
         let type_out = if let Some(tok) = self.peek() {
@@ -223,30 +504,67 @@ impl Parser {
         consume_token!(self, TokenType::EqArrow)?;
         let rhs = self.parse_expression()?;
 
-        Ok(NewFnAnon(token, arguments, type_out, rhs))
+        Ok(self.finish(start, NewFnAnon(token, arguments, type_out, rhs)))
     }
 
 
-    /// Parses a tuple of function arguments based on the following rule:
+    /// Parses a named function, whose generics are shared by both forms, 
+    /// based on the following rules:
     /// 
-    /// <fnSignature> ::= 'fn' <symbol> '::' <typeFn> ;
+    /// <fnSignature>   ::= 'fn' <symbol> [ <generics> ]? '::' <typeFn> [ <whereClause> ]? ;
+    /// <fnDeclaration> ::= 'fn' <symbol> [ <generics> ]? <fnArgs> [ ':' <typeFn> ]? 
+    ///                     [ <whereClause> ]? '=>' <expression> ;
     /// 
-    fn parse_fn_signature(&mut self) -> Result<Node, ParserError> {
+    fn parse_fn_named(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::Fn)?;
         let symbol = self.parse_symbol()?;
+        let generics = self.parse_generics()?;
+        match self.peek() {
+            Some(tok) if tok.typ == TokenType::DoubleColon => {
+                self.parse_fn_signature(start, token, symbol, generics)
+            },
+            Some(tok) if tok.typ == TokenType::LParen => {
+                self.parse_fn_declaration(start, token, symbol, generics)
+            },
+            Some(tok) => {
+                Err(new_parser_expected_one_of(tok.row, tok.col, vec!(
+                    format!("`::`"), format!("`(`"))
+                ))
+            },
+            None => {
+                let (r, c) = self.get_last_token_location();
+                Err(new_parser_expected_one_of(r, c + 1, vec!(
+                    format!("`::`"), format!("`(`"))
+                ))
+            }
+        }
+    }
+
+    /// Parses the rest of a function signature, after its generics, based on
+    /// the following rule:
+    /// 
+    /// <fnSignature> ::= 'fn' <symbol> [ <generics> ]? '::' <typeFn> [ <whereClause> ]? ;
+    /// 
+    fn parse_fn_signature(
+        &mut self, start: Span, token: Token, symbol: Node, generics: Vec<Node>,
+    ) -> Result<Node, ParserError> {
         consume_token!(self, TokenType::DoubleColon)?;
         let ttype = self.parse_type_fn()?;
+        let where_clause = self.parse_where_clause()?;
 
-        Ok(NewFnSignature(token, symbol, ttype))
+        Ok(self.finish(start, NewFnSignature(token, symbol, generics, ttype, where_clause)))
     }
 
-    /// Parses a tuple of function arguments based on the following rule:
+    /// Parses the rest of a function declaration, after its generics, based 
+    /// on the following rule:
     /// 
-    /// <fnDeclaration> ::= 'fn' <symbol> <fnArgs> [ ':' <typeFn> ]? '=>' <expression> ;
+    /// <fnDeclaration> ::= 'fn' <symbol> [ <generics> ]? <fnArgs> [ ':' <typeFn> ]? 
+    ///                     [ <whereClause> ]? '=>' <expression> ;
     /// 
-    fn parse_fn_declaration(&mut self) -> Result<Node, ParserError> {
-        let token = consume_token!(self, TokenType::Fn)?;
-        let symbol = self.parse_symbol()?;
+    fn parse_fn_declaration(
+        &mut self, start: Span, token: Token, symbol: Node, generics: Vec<Node>,
+    ) -> Result<Node, ParserError> {
         let arguments = self.parse_fn_args()?;
         let type_out = if let Some(tok) = self.peek() {
             match tok.typ {
@@ -257,11 +575,14 @@ impl Parser {
                 _ => { None }
             }
         } else { None };
+        let where_clause = self.parse_where_clause()?;
         
         consume_token!(self, TokenType::EqArrow)?;
         let rhs = self.parse_expression()?;
 
-        Ok(NewFnDeclaration(token, symbol, rhs, arguments, type_out))
+        Ok(self.finish(start, NewFnDeclaration(
+            token, symbol, generics, rhs, arguments, type_out, where_clause,
+        )))
     }
 
     /// Parses a tuple of function arguments based on the following rule:
@@ -291,41 +612,21 @@ impl Parser {
         Ok(args)
     }
 
-    /// Parses an if expression based on the following rule:
+    /// Parses a function argument based on the following rule:
     /// 
-    /// <fnArgsTyped>   ::= <symbol> [ ':' <typeFn> ] ? 
-    ///                              | <listExpr> 
-    ///                              | <integer>
-    ///                              | <string> ;
+    /// <fnArgsTyped>   ::= <pattern> [ ':' <typeFn> ]? ;
     /// 
     fn parse_fn_arg(&mut self) -> Result <Node, ParserError> {
-        if let Some(tok) = self.peek() {
-            match tok.typ {
-                TokenType::LBracket   => { return self.parse_list(); },
-                TokenType::String(_)  => { return self.parse_string(); },
-                TokenType::Integer(_) => { return self.parse_integer(); },
-                TokenType::Symbol(_)  => {
-                    if let Some(tok2) = self.peek_twice() {
-                        if tok2.typ == TokenType::LParen {
-                            return self.parse_call();
-                        }
-                    }
-                },
-                _ => {},
-            } 
-            
-        };
-
-        let symbol = self.parse_symbol()?;
-        if let Some(tok) = self.peek() {
-            match tok.typ {
-                TokenType::Colon => {
-                    self.next();
-                    Ok(NewFnArgTyped(symbol, Some(self.parse_type_fn()?)))
-                },
-                _ => { Ok(symbol) }
-            }
-        } else { Ok(symbol) }
+        let start = self.span_start();
+        let pattern = self.parse_pattern()?;
+        match self.peek() {
+            Some(tok) if tok.typ == TokenType::Colon => {
+                self.next();
+                let ttype = self.parse_type_fn()?;
+                Ok(self.finish(start, NewFnArgTyped(pattern, Some(ttype))))
+            },
+            _ => Ok(pattern),
+        }
     }
 
     /// Parses an if expression based on the following rule:
@@ -333,6 +634,7 @@ impl Parser {
     /// <ifExpr> ::= 'if' <or> 'then' <expression> [ 'else' <expression> ]? ;
     /// 
     fn parse_if(&mut self) -> Result <Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::If)?;
         let cond = self.parse_expression()?;
         consume_token!(self, TokenType::Then)?;
@@ -346,40 +648,36 @@ impl Parser {
                 _ => {None},
             }
         } else { None };
-        Ok(NewIfExpr(token, cond, true_branch, false_branch))
+        Ok(self.finish(start, NewIfExpr(token, cond, true_branch, false_branch)))
     }
 
     fn parse_pub(&mut self) -> Result<Node, ParserError> {
-        Ok (NewPubExpr(
+        let start = self.span_start();
+        let node = NewPubExpr(
             consume_token!(self, TokenType::Pub)?,
             self.parse_expression()?,
-        ))
+        );
+        Ok(self.finish(start, node))
     }
 
     /// Parses a data declaration based on the following rule:
     /// 
-    /// <dataExpr>      ::= 'data' <symbol> [ <dataGenerics> ]? '(' <dataVariants> ')' ;
+    /// <dataExpr>      ::= 'data' <symbol> [ <generics> ]? [ <whereClause> ]? '(' <dataVariants> ')' ;
     /// <dataVariants>  ::= [ '|' ]? <dataItem> [ '|' <dataItem> ]* ;
     /// 
     fn parse_data(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::Data)?;
         let symbol = self.parse_symbol()?;
-        let mut generics = Vec::new();
+        let generics = self.parse_generics()?;
+        let where_clause = self.parse_where_clause()?;
         let mut variants = Vec::new();
-        if let Some(tok) = self.peek() {
-            match tok.typ {
-                TokenType::Less => {
-                    generics = self.parse_data_generics()?;
-                }
-                _ => {},
-            };
-        };
         
         let mut first = true;
         consume_token!(self, TokenType::LParen)?;
         while let Some(tok2) = self.peek() {
-            if tok2.typ == TokenType::Bar {
-                if first { consume_token!(self, TokenType::Bar)?; }
+            if tok2.typ == TokenType::Bar && first {
+                consume_token!(self, TokenType::Bar)?;
             };
             match tok2.typ {
                 TokenType::RParen => { break },
@@ -392,24 +690,60 @@ impl Parser {
         }
 
         consume_token!(self, TokenType::RParen)?;
-        Ok(NewDataDelcaration(token, symbol, generics, variants))
+        Ok(self.finish(start, NewDataDelcaration(token, symbol, generics, where_clause, variants)))
+    }
+
+    /// Parses a type alias based on the following rule:
+    /// 
+    /// <typeAlias> ::= 'type' <symbol> [ <generics> ]? '=' <typeFn> ;
+    /// 
+    fn parse_type_alias(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::Type)?;
+        let symbol = self.parse_symbol()?;
+        let generics = self.parse_generics()?;
+        consume_token!(self, TokenType::Bind)?;
+        let ttype = self.parse_type_fn()?;
+
+        Ok(self.finish(start, NewTypeAlias(token, symbol, generics, ttype)))
+    }
+
+    /// Parses a newtype declaration based on the following rule:
+    /// 
+    /// <newtypeExpr> ::= 'newtype' <symbol> [ <generics> ]? '=' <typeFn> ;
+    /// 
+    fn parse_newtype(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::Newtype)?;
+        let symbol = self.parse_symbol()?;
+        let generics = self.parse_generics()?;
+        consume_token!(self, TokenType::Bind)?;
+        let ttype = self.parse_type_fn()?;
+
+        Ok(self.finish(start, NewNewtype(token, symbol, generics, ttype)))
     }
 
-    /// Parses a generic data pattern based on the following rule:
+    /// Parses the generic parameters of a declaration, if there are any, 
+    /// based on the following rule:
     /// 
-    /// <dataGenerics>  ::= '<' <symbol> [',' <symbol> ]* '>' ;
+    /// <generics>  ::= '<' <genericParam> [ ',' <genericParam> ]* '>' ;
     /// 
-    fn parse_data_generics(&mut self) -> Result<Vec<Node>, ParserError> {
+    /// Returns a vector of nodes rather than a single node.
+    fn parse_generics(&mut self) -> Result<Vec<Node>, ParserError> {
         let mut generics = Vec::new();
+        match self.peek() {
+            Some(tok) if tok.typ == TokenType::Less => {},
+            _ => return Ok(generics),
+        };
+
         let mut first = true;
         consume_token!(self, TokenType::Less)?;
-
-        while let Some(tok) = self.peek() {
-            match tok.typ {
-                TokenType::Greater => { break },
-                _ => {
+        while self.peek().is_some() {
+            match self.at_closing_angle() {
+                true => { break },
+                false => {
                     if !first { consume_token!(self, TokenType::Comma)?; }
-                    generics.push(self.parse_symbol()?);
+                    generics.push(self.parse_generic_param(false)?);
                     first = false;
                 }
             };
@@ -419,25 +753,87 @@ impl Parser {
         Ok(generics)
     }
 
+    /// Parses a `where` clause, if there is one, based on the following rule:
+    /// 
+    /// <whereClause>   ::= 'where' <genericParam> [ ',' <genericParam> ]* ;
+    /// 
+    /// Every parameter of a where clause must have bounds. Returns a vector of
+    /// nodes rather than a single node.
+    fn parse_where_clause(&mut self) -> Result<Vec<Node>, ParserError> {
+        let mut params = Vec::new();
+        match self.peek() {
+            Some(tok) if tok.typ == TokenType::Where => {},
+            _ => return Ok(params),
+        };
+
+        consume_token!(self, TokenType::Where)?;
+        params.push(self.parse_generic_param(true)?);
+        while let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::Comma => {
+                    consume_token!(self, TokenType::Comma)?;
+                    params.push(self.parse_generic_param(true)?);
+                },
+                _ => break,
+            };
+        };
+
+        Ok(params)
+    }
+
+    /// Parses a generic parameter and its trait bounds based on the following
+    /// rules:
+    /// 
+    /// <genericParam>  ::= <symbol> [ ':' <bounds> ]? ;
+    /// <bounds>        ::= <typeCmpst> [ '+' <typeCmpst> ]* ;
+    /// 
+    /// A parameter without bounds is returned as a plain symbol.
+    fn parse_generic_param(&mut self, bounded: bool) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let symbol = self.parse_symbol()?;
+        match self.peek() {
+            Some(tok) if tok.typ == TokenType::Colon => {},
+            _ if bounded => {
+                consume_token!(self, TokenType::Colon)?;
+            },
+            _ => return Ok(symbol),
+        };
+
+        consume_token!(self, TokenType::Colon)?;
+        let mut bounds = vec![self.parse_type_cmpst()?];
+        while let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::Plus => {
+                    consume_token!(self, TokenType::Plus)?;
+                    bounds.push(self.parse_type_cmpst()?);
+                },
+                _ => break,
+            };
+        };
+
+        Ok(self.finish(start, NewGenericParam(symbol, bounds)))
+    }
+
     /// Parses a data item pattern based on the following rule:
     /// 
     /// <dataItem>      ::= <symbol> [ '::' ( <typeFn> )]? ;
     /// 
     fn parse_data_item(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let symbol = self.parse_symbol()?;
         if let Some(tok) = self.peek() {
             if tok.typ == TokenType::DoubleColon {
                 consume_token!(self, TokenType::DoubleColon)?;
-                if let Some(tok2) = self.peek() {
+                let ttype = if let Some(tok2) = self.peek() {
                     if tok2.typ == TokenType::Struct {
-                        println!("----");
-                        Ok(NewDataItem(symbol, self.parse_struct_anon()?))
+                        self.parse_struct_anon()?
                     } else {
-                        Ok(NewDataItem(symbol, self.parse_type_fn()?))
+                        self.parse_type_fn()?
                     }
                 } else {
-                    Ok(NewDataItem(symbol, self.parse_type_fn()?))
-                }
+                    self.parse_type_fn()?
+                };
+                Ok(self.finish(start, NewDataItem(symbol, ttype)))
             } else {
                 Ok(symbol)
             }
@@ -467,6 +863,7 @@ impl Parser {
     /// <structAnon> ::= 'struct' '(' <structFields> ')' ;
     /// 
     fn parse_struct_anon(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::Struct)?;
         consume_token!(self, TokenType::LParen)?;
         let mut fields = Vec::new();
@@ -486,17 +883,21 @@ impl Parser {
         }
 
         consume_token!(self, TokenType::RParen)?;
-        Ok(NewStructAnon(token, fields))
+        Ok(self.finish(start, NewStructAnon(token, fields)))
     }
     
     /// Parses a struct declaration based on the following rules:
     /// 
-    /// <structDeclaration> ::= 'struct' <symbol> '(' <structFields> ')' ;
+    /// <structDeclaration> ::= 'struct' <symbol> [ <generics> ]? [ <whereClause> ]? 
+    ///                         '(' <structFields> ')' ;
     /// <structFields>      ::= <structField> [ ',' <structField> ]* [ ',' ]? ;
     /// 
     fn parse_struct_declaration(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let struct_tok = consume_token!(self, TokenType::Struct)?;
         let name = self.parse_symbol()?;
+        let generics = self.parse_generics()?;
+        let where_clause = self.parse_where_clause()?;
         consume_token!(self, TokenType::LParen)?;
         let mut fields = Vec::new();
         
@@ -515,7 +916,7 @@ impl Parser {
         }
         
         consume_token!(self, TokenType::RParen)?;
-        Ok(NewStructDeclaration(struct_tok, name, fields))
+        Ok(self.finish(start, NewStructDeclaration(struct_tok, name, generics, where_clause, fields)))
     }
 
     /// Parses a struct field pattern based on the following rule:
@@ -523,65 +924,350 @@ impl Parser {
     /// <structField>       ::= <symbol> '::' <typeFn> ;
     /// 
     fn parse_struct_field(&mut self) -> Result<Node, ParserError> {
-        println!("struct field");
+        let start = self.span_start();
         let symbol = self.parse_symbol()?;
         consume_token!(self, TokenType::DoubleColon)?;
         let ttype = self.parse_type_fn()?;
         
-        Ok(NewStructField(symbol, ttype))
+        Ok(self.finish(start, NewStructField(symbol, ttype)))
     }
 
-    /// Parses a package statement based on the following rule:
+    /// Parses a trait declaration based on the following rule:
     /// 
-    /// <packageStmt>   ::= 'package' <symbol> ;
+    /// <traitExpr> ::= 'trait' <symbol> [ <generics> ]? [ <whereClause> ]? '(' [ <fnItem> ';' ]* ')' ;
     /// 
-    fn parse_package(&mut self) -> Result<Node, ParserError> {
-        let token = consume_token!(self, TokenType::Package)?;
+    fn parse_trait(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::Trait)?;
+        let symbol = self.parse_symbol()?;
+        let generics = self.parse_generics()?;
+        let where_clause = self.parse_where_clause()?;
 
-        Ok(NewPackage(self.parse_symbol()?, token))
+        let methods = self.parse_fn_items()?;
+        Ok(self.finish(start, NewTraitDeclaration(token, symbol, generics, where_clause, methods)))
     }
 
-    /// Parses an import statement based on the following rule:
+    /// Parses an impl block, for either a trait or the type itself, based on
+    /// the following rule:
     /// 
-    /// <importStmt>        ::= 'import' [ <string> | <tupleStrings> ] ;
+    /// <implExpr> ::= 'impl' [ <typeCmpst> 'for' ]? <typeCmpst> <fnItems> ;
     /// 
-    fn parse_import(&mut self) -> Result<Node, ParserError> {
-        let token = consume_token!(self, TokenType::Import)?;
-
+    fn parse_impl(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::Impl)?;
+        let mut ttrait = None;
+        let mut ttype = self.parse_type_cmpst()?;
         if let Some(tok) = self.peek() {
-            if tok.typ == TokenType::LParen {
-                consume_token!(self, TokenType::LParen)?;
-                let mut imports = Vec::new();
-                let mut first = true;
-                while let Some(tok2) = self.peek() {
-                    match tok2.typ {
-                        TokenType::RParen => { break },
-                        _ => {
-                            if !first { consume_token!(self, TokenType::Comma)?; }
-                            imports.push(self.parse_string()?);
-                            first = false;
-                        }
-                    };
-                };
-
-                consume_token!(self, TokenType::RParen)?;
-                return Ok(NewImport(imports, token))
-            } else {
-                Ok(NewImport(vec![self.parse_string()?], token))
+            if tok.typ == TokenType::For {
+                consume_token!(self, TokenType::For)?;
+                ttrait = Some(ttype);
+                ttype = self.parse_type_cmpst()?;
             }
-        } else {
+        };
+
+        let items = self.parse_fn_items()?;
+        Ok(self.finish(start, NewImplBlock(token, ttrait, ttype, items)))
+    }
+
+    /// Parses a block of named functions, as found in trait declarations and
+    /// impl blocks, based on the following rules:
+    /// 
+    /// <fnItems>   ::= '(' [ <fnItem> ';' ]* ')' ;
+    /// <fnItem>    ::= [ <decorator> ]* [ <fnSignature> | <fnDeclaration> ] ;
+    /// 
+    /// Returns a vector of nodes rather than a single node.
+    fn parse_fn_items(&mut self) -> Result<Vec<Node>, ParserError> {
+        let mut items = Vec::new();
+        consume_token!(self, TokenType::LParen)?;
+
+        while let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::RParen => { break },
+                _ => items.push(self.recover(Some(TokenType::RParen), Parser::parse_fn_item)),
+            };
+        };
+
+        consume_token!(self, TokenType::RParen)?;
+        Ok(items)
+    }
+
+    /// Parses a single item of an `<fnItems>` list, including its `;`.
+    fn parse_fn_item(&mut self) -> Result<Node, ParserError> {
+        let tok = match self.peek() {
+            Some(tok) => tok,
+            None => {
+                let (r, c) = self.get_last_token_location();
+                return Err(new_parser_expected(r, c + 1, "`)`".to_string()))
+            }
+        };
+        let item = match tok.typ {
+            TokenType::Decorator(_) => self.parse_decorated(Parser::parse_method)?,
+            TokenType::Fn => self.parse_method()?,
+            _ => return Err(new_parser_expected_one_of(tok.row, tok.col, vec![
+                format!("`fn`"), format!("`)`")
+            ])),
+        };
+        self.end_complete_statement()?;
+
+        Ok(item)
+    }
+
+    /// Parses the function of an `<fnItem>`, which must be named.
+    fn parse_method(&mut self) -> Result<Node, ParserError> {
+        let tok = match self.peek() {
+            Some(tok) if tok.typ == TokenType::Fn => tok,
+            Some(tok) => return Err(new_parser_expected(tok.row, tok.col, "`fn`".to_string())),
+            None => {
+                let (r, c) = self.get_last_token_location();
+                return Err(new_parser_expected(r, c + 1, "`fn`".to_string()))
+            }
+        };
+        match self.peek_twice() {
+            Some(tok2) if matches!(tok2.typ, TokenType::Symbol(_)) => {},
+            Some(tok2) => {
+                return Err(new_parser_expected(tok2.row, tok2.col, "<symbol>".to_string()))
+            },
+            None => {
+                return Err(new_parser_expected(tok.row, tok.col + 2, "<symbol>".to_string()))
+            },
+        };
+
+        self.parse_function()
+    }
+
+    /// Parses a declaration preceded by decorators based on the following
+    /// rules:
+    /// 
+    /// <decorated> ::= [ <decorator> ]+ [ <fnSignature> 
+    ///                                  | <fnDeclaration> 
+    ///                                  | <dataExpr> 
+    ///                                  | <typeExpr> 
+    ///                                  | <structDeclaration> 
+    ///                                  | <traitExpr> 
+    ///                                  | <implExpr> 
+    ///                                  | <pubExpr> ] ;
+    /// 
+    /// `parse` parses the declaration after the decorators, so that places
+    /// which only allow some declarations, like `<fnItems>`, can say which.
+    fn parse_decorated<F>(&mut self, parse: F) -> Result<Node, ParserError>
+    where F: FnOnce(&mut Parser) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let mut decorators = Vec::new();
+        while let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::Decorator(_) => decorators.push(self.parse_decorator()?),
+                _ => break,
+            };
+        };
+
+        let (r, c) = match self.peek() {
+            Some(tok) => (tok.row, tok.col),
+            None => {
+                let (r, c) = self.get_last_token_location();
+                (r, c + 1)
+            }
+        };
+        let mut node = parse(self)?;
+        match node.decorators_mut() {
+            Some(node_decorators) => *node_decorators = decorators,
+            None => return Err(new_parser_expected(r, c, "<declaration>".to_string())),
+        };
+
+        Ok(self.finish(start, node))
+    }
+
+    /// Parses a single decorator based on the following rule:
+    /// 
+    /// <decorator> ::= '@' <symbol> [ <tupleAny> ]? ;
+    /// 
+    fn parse_decorator(&mut self) -> Result<Decorator, ParserError> {
+        let start = self.span_start();
+        let token = match self.next() {
+            Some(tok) => tok,
+            None => {
+                let (r, c) = self.get_last_token_location();
+                return Err(new_parser_expected(r, c, "<decorator>".to_string()))
+            }
+        };
+        let name = match &token.typ {
+            TokenType::Decorator(name) => name.clone(),
+            _ => return Err(new_parser_expected(token.row, token.col, "<decorator>".to_string())),
+        };
+        let mut args = Vec::new();
+        if let Some(tok) = self.peek() {
+            if tok.typ == TokenType::LParen {
+                args = self.parse_tuple_vec()?;
+            }
+        };
+
+        let mut decorator = NewDecorator(token, name, args);
+        decorator.span = start.to(self.prev_span());
+        Ok(decorator)
+    }
+
+    /// Parses a fixity declaration based on the following rule:
+    /// 
+    /// <fixityDecl> ::= ( 'infixl' | 'infixr' | 'infix' ) <integer> 
+    ///                  <operator> [ ',' <operator> ]* ;
+    /// 
+    /// The declared operators are added to the operator table, so they 
+    /// are honored for the remainder of the package.
+    fn parse_fixity(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = match self.next() {
+            Some(tok) => tok,
+            None => {
+                let (r, c) = self.get_last_token_location();
+                return Err(new_parser_expected(r, c, "<fixityDecl>".to_string()))
+            }
+        };
+        let assoc = match token.typ {
+            TokenType::Infixl => Assoc::Left,
+            TokenType::Infixr => Assoc::Right,
+            TokenType::Infix  => Assoc::None,
+            _ => return Err(new_parser_expected_one_of(token.row, token.col, vec![
+                "infixl".to_string(), "infixr".to_string(), "infix".to_string(),
+            ])),
+        };
+
+        let bp = match self.peek() {
+            Some(tok) => match tok.typ {
+                TokenType::Integer(n) if (0..=9).contains(&n) => fixity_bp(n),
+                TokenType::Integer(n) => return Err(new_parser_invalid_fixity(tok.row, tok.col, n)),
+                _ => return Err(new_parser_expected(tok.row, tok.col, "<integer>".to_string())),
+            },
+            None => {
+                let (r, c) = self.get_last_token_location();
+                return Err(new_parser_expected(r, c + 1, "<integer>".to_string()))
+            }
+        };
+        let level = self.parse_integer()?;
+
+        let mut operators = vec![self.parse_operator()?];
+        while let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::Comma => {
+                    self.next();
+                    operators.push(self.parse_operator()?);
+                },
+                _ => break,
+            };
+        };
+
+        for op in &operators {
+            if let Node::Atom(atom) = op {
+                self.operators.add_infix(atom.token.typ.clone(), bp, assoc, Handler::Binary);
+            }
+        }
+
+        Ok(self.finish(start, NewFixityDeclaration(token, level, operators)))
+    }
+
+    /// Parses a package statement based on the following rule:
+    /// 
+    /// <packageStmt>   ::= 'package' <symbol> ;
+    /// 
+    fn parse_package(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::Package)?;
+        let symbol = self.parse_symbol()?;
+
+        Ok(self.finish(start, NewPackage(symbol, token)))
+    }
+
+    /// Parses an import statement based on the following rule:
+    /// 
+    /// <importStmt>    ::= 'import' [ <string> [ 'as' <symbol> ]? [ <importItems> ]? 
+    ///                              | <tupleStrings> ] ;
+    /// 
+    /// An alias and an item list can only follow a single package.
+    fn parse_import(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::Import)?;
+
+        if let Some(tok) = self.peek() {
+            if tok.typ == TokenType::LParen {
+                consume_token!(self, TokenType::LParen)?;
+                let mut imports = Vec::new();
+                let mut first = true;
+                while let Some(tok2) = self.peek() {
+                    match tok2.typ {
+                        TokenType::RParen => { break },
+                        _ => {
+                            if !first { consume_token!(self, TokenType::Comma)?; }
+                            imports.push(self.parse_string()?);
+                            first = false;
+                        }
+                    };
+                };
+
+                consume_token!(self, TokenType::RParen)?;
+                Ok(self.finish(start, NewImport(imports, token)))
+            } else {
+                let import = self.parse_string()?;
+                let alias = match self.peek() {
+                    Some(tok2) if tok2.typ == TokenType::As => {
+                        consume_token!(self, TokenType::As)?;
+                        Some(self.parse_symbol()?)
+                    },
+                    _ => None,
+                };
+                let (items, hiding) = match self.peek() {
+                    Some(tok2) if tok2.typ == TokenType::LParen => {
+                        (self.parse_import_items()?, Vec::new())
+                    },
+                    Some(tok2) if tok2.typ == TokenType::Hiding => {
+                        consume_token!(self, TokenType::Hiding)?;
+                        (Vec::new(), self.parse_import_items()?)
+                    },
+                    _ => (Vec::new(), Vec::new()),
+                };
+                Ok(self.finish(start, NewImportWith(vec![import], alias, items, hiding, token)))
+            }
+        } else {
             let (r, c) = self.get_last_token_location();
-            return Err(new_parser_expected_one_of(r,c, vec![
+            Err(new_parser_expected_one_of(r,c, vec![
                 "(".to_string(), "<string>".to_string()
-            ]));
+            ]))
         }
     }
 
+    /// Parses the names listed after an imported package based on the 
+    /// following rules:
+    /// 
+    /// <importItems>   ::= [ 'hiding' ]? '(' <importItem> [ ',' <importItem> ]* [ ',' ]? ')' ;
+    /// <importItem>    ::= <symbol> | <operator> ;
+    /// 
+    /// The `hiding` keyword is consumed by the caller. Returns a vector of 
+    /// nodes rather than a single node.
+    fn parse_import_items(&mut self) -> Result<Vec<Node>, ParserError> {
+        let mut items = Vec::new();
+        consume_token!(self, TokenType::LParen)?;
+
+        while let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::RParen => { break },
+                TokenType::Operator(_) => items.push(self.parse_operator()?),
+                _ => items.push(self.parse_symbol()?),
+            };
+            match self.peek() {
+                Some(tok2) if tok2.typ == TokenType::Comma => {
+                    consume_token!(self, TokenType::Comma)?;
+                },
+                _ => break,
+            };
+        };
+
+        consume_token!(self, TokenType::RParen)?;
+        Ok(items)
+    }
+
     /// Parses a match expression based on the following rule:
     /// 
     /// <matchExpr> ::= 'match' <expression> 'with' '(' [ <matchBranch> ]* ')' ;
     /// 
     fn parse_match(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::Match)?;
         let lhs = self.parse_expression()?;
         consume_token!(self, TokenType::With)?;
@@ -596,28 +1282,42 @@ impl Parser {
         }
         
         consume_token!(self, TokenType::RParen)?;
-        Ok(NewMatchExpression(token, lhs, branches))
+        Ok(self.finish(start, NewMatchExpression(token, lhs, branches)))
     }
 
 
     /// Parses a match branch pattern based on the following rule:
     /// 
-    /// <matchBranch>   ::= '|' <expression> '->' <expression>
+    /// <matchBranch>   ::= '|' <pattern> [ 'if' <or> ]? '->' <expression>
     /// 
     fn parse_match_branch(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::Bar)?;
-        let lhs = self.parse_expression()?;
+        let lhs = self.parse_pattern()?;
+        let guard = match self.peek() {
+            Some(tok) if tok.typ == TokenType::If => {
+                self.next();
+                Some(self.parse_or()?)
+            },
+            _ => None,
+        };
         consume_token!(self, TokenType::Arrow)?;
-        let rhs = self.parse_expression()?;
+        let rhs = self.restricted(
+            Restrictions { no_bar: true, ignore_newlines: true, ..Restrictions::default() }, 
+            Parser::parse_expression)?;
         
-        Ok(NewMatchBranch(token, lhs, rhs))
+        Ok(self.finish(start, NewMatchBranch(token, lhs, guard, rhs)))
     }
 
     /// Parses a list pattern based on the following rule:
     /// 
-    /// <listExpr>      ::= <listSplit> | <listLiteral> ;
+    /// <listExpr>      ::= <listComp> | <listSplit> | <listLiteral> ;
     /// 
+    /// A list containing a `<-` is a comprehension.
     fn parse_list(&mut self) -> Result<Node, ParserError> {
+        if self.is_comprehension(self.cursor + 1) {
+            return self.parse_list_comprehension();
+        }
         if let Some(tok) = self.peek_twice() {
             match tok.typ {
                 TokenType::RBracket => self.parse_list_literal(),
@@ -625,7 +1325,7 @@ impl Parser {
                     if let Some(tok2) = self.peek_thrice() {
                         match tok2.typ {
                             TokenType::Bar => self.parse_list_split(),
-                            TokenType::RBracket | _ => self.parse_list_literal(),
+                            _ => self.parse_list_literal(),
                         }
                     } else { self.parse_list_literal() }
                 },
@@ -642,13 +1342,60 @@ impl Parser {
     /// <listSplit>     ::= '[' <symbol> '|' <symbol> ']' ;
     /// 
     fn parse_list_split(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         consume_token!(self, TokenType::LBracket)?;
         let h = self.parse_symbol()?;
         consume_token!(self, TokenType::Bar)?;
         let t = self.parse_symbol()?;
         consume_token!(self, TokenType::RBracket)?;
         
-        Ok(NewListSplit(h, t))
+        Ok(self.finish(start, NewListSplit(h, t)))
+    }
+
+    /// Parses a list comprehension based on the following rules:
+    /// 
+    /// <listComp>   ::= '[' <expression> '|' <compClause> [ ',' <compClause> ]* ']' ;
+    /// <compClause> ::= <generator> | <expression> ;
+    /// 
+    fn parse_list_comprehension(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::LBracket)?;
+        let expr = self.restricted(
+            Restrictions { no_bar: true, ignore_newlines: true, ..Restrictions::default() }, 
+            Parser::parse_expression)?;
+        consume_token!(self, TokenType::Bar)?;
+
+        let mut clauses = vec![self.parse_comprehension_clause()?];
+        while let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::Comma => {
+                    self.next();
+                    clauses.push(self.parse_comprehension_clause()?);
+                },
+                _ => break,
+            };
+        };
+        consume_token!(self, TokenType::RBracket)?;
+
+        Ok(self.finish(start, NewListComprehension(token, expr, clauses)))
+    }
+
+    /// Parses a generator or a filter of a list comprehension based on the
+    /// following rule:
+    /// 
+    /// <generator>  ::= <pattern> '<-' <expression> ;
+    /// 
+    fn parse_comprehension_clause(&mut self) -> Result<Node, ParserError> {
+        if !self.item_contains(self.cursor, &TokenType::LeftArrow) {
+            return self.parse_delimited();
+        }
+
+        let start = self.span_start();
+        let pattern = self.parse_pattern()?;
+        let token = consume_token!(self, TokenType::LeftArrow)?;
+        let iter = self.parse_delimited()?;
+
+        Ok(self.finish(start, NewGenerator(token, pattern, iter)))
     }
 
     /// Parses a list literal based on the following rule:
@@ -657,6 +1404,7 @@ impl Parser {
     /// <listItems>     ::= <expression> [ ',' <expression> ]* ;
     /// 
     fn parse_list_literal(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let token = consume_token!(self, TokenType::LBracket)?;
         let mut items = Vec::new();
         let mut first = true;
@@ -668,37 +1416,220 @@ impl Parser {
                     if !first {
                         consume_token!(self, TokenType::Comma)?;
                     }
-                    items.push(self.parse_expression()?);
+                    items.push(self.parse_delimited()?);
                     first = false;
                 }
             }
         }
         
         consume_token!(self, TokenType::RBracket)?;
-        Ok(NewListExpression(token, items))
+        Ok(self.finish(start, NewListExpression(token, items)))
     }
 
-    /// Parses a while expression pattern based on the following rule:
+    /// Parses a pattern based on the following rule:
     /// 
-    /// <whileExpr>     ::= 'while' <or> '{' [ <statement> ]* '}' ;
+    /// <pattern> ::= <asPattern> [ '|' <asPattern> ]* ;
     /// 
-    fn parse_while(&mut self) -> Result<Node, ParserError> {
-        let token = consume_token!(self, TokenType::While)?;
-        let cond = self.parse_expression()?;
-        let mut stmts = Vec::new();
-        consume_token!(self, TokenType::LCurly)?;
+    fn parse_pattern(&mut self) -> Result<Node, ParserError> {
+        self.nested(|p| {
+            let start = p.span_start();
+            let mut patterns = vec![p.parse_as_pattern()?];
+            while let Some(tok) = p.peek() {
+                match tok.typ {
+                    TokenType::Bar => {
+                        p.next();
+                        patterns.push(p.parse_as_pattern()?);
+                    },
+                    _ => break,
+                };
+            };
+
+            match patterns.len() {
+                1 => Ok(patterns.pop().unwrap()),
+                _ => Ok(p.finish(start, NewOrPattern(patterns))),
+            }
+        })
+    }
+
+    /// Parses an as pattern based on the following rule:
+    /// 
+    /// <asPattern> ::= <patternAtom> [ 'as' <symbol> ]? ;
+    /// 
+    fn parse_as_pattern(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let pattern = self.parse_pattern_atom()?;
+        match self.peek() {
+            Some(tok) if tok.typ == TokenType::As => {
+                let token = consume_token!(self, TokenType::As)?;
+                let symbol = self.parse_symbol()?;
+                Ok(self.finish(start, NewAsPattern(token, pattern, symbol)))
+            },
+            _ => Ok(pattern),
+        }
+    }
+
+    /// Parses a single pattern based on the following rule:
+    /// 
+    /// <patternAtom> ::= '_'
+    ///                 | <symbol> [ '(' [ <pattern> [ ',' <pattern> ]* ]? ')' ]?
+    ///                 | [ '-' ]? <integer> | [ '-' ]? <float>
+    ///                 | <string> | <bool> | 'None'
+    ///                 | <unit>
+    ///                 | '(' <pattern> ')'
+    ///                 | <tuplePattern>
+    ///                 | <listPattern> ;
+    /// 
+    fn parse_pattern_atom(&mut self) -> Result<Node, ParserError> {
+        let tok = match self.peek() {
+            Some(tok) => tok,
+            None => {
+                let (r, c) = self.get_last_token_location();
+                return Err(new_parser_expected(r, c + 1, "<pattern>".to_string()))
+            }
+        };
+
+        match tok.typ {
+            TokenType::Symbol(ref name) if name == "_" => {
+                self.next();
+                Ok(NewWildcardPattern(tok))
+            },
+            TokenType::Symbol(_) => {
+                let start = self.span_start();
+                let symbol = self.parse_symbol()?;
+                match self.peek() {
+                    Some(tok2) if tok2.typ == TokenType::LParen => {
+                        let args = self.parse_pattern_tuple()?;
+                        Ok(self.finish(start, NewConstructorPattern(symbol, args)))
+                    },
+                    _ => Ok(symbol),
+                }
+            },
+            TokenType::Integer(_) | TokenType::Float(_) | TokenType::String(_)
+            | TokenType::Bool(_) | TokenType::None => {
+                self.next();
+                Ok(NewAtom(tok))
+            },
+            TokenType::Minus => self.parse_negative_pattern(tok),
+            TokenType::LParen => self.parse_tuple_pattern(),
+            TokenType::LBracket => self.parse_list_pattern(),
+            _ => Err(new_parser_expected(tok.row, tok.col, "<pattern>".to_string())),
+        }
+    }
+
+    /// Parses a negative number literal as a pattern. The `-` and the number
+    /// are folded into a single atom token spanning both.
+    fn parse_negative_pattern(&mut self, minus: Token) -> Result<Node, ParserError> {
+        let typ = match self.peek_twice().map(|tok| tok.typ) {
+            Some(TokenType::Integer(n)) => TokenType::Integer(n.wrapping_neg()),
+            Some(TokenType::Float(n)) => TokenType::Float(-n),
+            _ => return Err(new_parser_expected(minus.row, minus.col, "<pattern>".to_string())),
+        };
+        self.next();
+        let number = self.next().unwrap();
+        Ok(NewAtom(Token {
+            typ,
+            span: minus.span.to(number.span),
+            trailing: number.trailing,
+            ..minus
+        }))
+    }
+
+    /// Parses a tuple pattern based on the following rule:
+    /// 
+    /// <tuplePattern> ::= '(' <pattern> ',' <pattern> [ ',' <pattern> ]* ')' ;
+    /// 
+    /// `()` is the unit pattern and a single parenthesized pattern is just 
+    /// grouped.
+    fn parse_tuple_pattern(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = self.peek();
+        let mut items = self.parse_pattern_tuple()?;
+        match (items.len(), token) {
+            (0, Some(token)) => Ok(self.finish(start, NewUnit(token))),
+            (1, _) => Ok(items.pop().unwrap()),
+            _ => Ok(self.finish(start, NewTuplePattern(items))),
+        }
+    }
+
+    /// Same as `parse_tuple_pattern` but returns a vector of nodes rather 
+    /// than a single node.
+    fn parse_pattern_tuple(&mut self) -> Result<Vec<Node>, ParserError> {
+        let mut patterns = Vec::new();
+        let mut first = true;
+
+        consume_token!(self, TokenType::LParen)?;
+        while let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::RParen => { break },
+                _ => {
+                    if !first { consume_token!(self, TokenType::Comma)?; }
+                    patterns.push(self.parse_pattern()?);
+                    first = false;
+                }
+            };
+        };
+        consume_token!(self, TokenType::RParen)?;
+
+        Ok(patterns)
+    }
+
+    /// Parses a list pattern based on the following rule:
+    /// 
+    /// <listPattern> ::= '[' [ <asPattern> [ ',' <asPattern> ]* 
+    ///                         [ '|' <asPattern> ]? ]? ']' ;
+    /// 
+    /// Or-patterns inside a list need parentheses, since `|` starts the rest.
+    fn parse_list_pattern(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let mut items = Vec::new();
+        let mut rest = None;
+
+        consume_token!(self, TokenType::LBracket)?;
         while let Some(tok) = self.peek() {
             match tok.typ {
-                TokenType::RCurly => {
-                    // self.next();
+                TokenType::RBracket => { break },
+                TokenType::Bar if !items.is_empty() => {
+                    self.next();
+                    rest = Some(self.nested(Parser::parse_as_pattern)?);
                     break;
+                },
+                _ => {
+                    if !items.is_empty() { consume_token!(self, TokenType::Comma)?; }
+                    items.push(self.nested(Parser::parse_as_pattern)?);
                 }
-                _ => {}
             };
-            stmts.push(self.parse_statement()?);
-        }
-        consume_token!(self, TokenType::RCurly)?;
-        Ok(NewWhileExpression(token, cond, stmts))
+        };
+        consume_token!(self, TokenType::RBracket)?;
+
+        Ok(self.finish(start, NewListPattern(items, rest)))
+    }
+
+    /// Parses a while expression pattern based on the following rule:
+    /// 
+    /// <whileExpr>     ::= 'while' <or> '{' [ <statement> ]* '}' ;
+    /// 
+    fn parse_while(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::While)?;
+        let cond = self.restricted(Restrictions { no_struct_literal: true, ..Restrictions::default() }, 
+            Parser::parse_expression)?;
+        let stmts = self.parse_block()?;
+        Ok(self.finish(start, NewWhileExpression(token, cond, stmts)))
+    }
+
+    /// Parses a for loop based on the following rule:
+    /// 
+    /// <forExpr>       ::= 'for' <binder> 'in' <expression> '{' [ <statement> ]* '}' ;
+    /// 
+    fn parse_for(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::For)?;
+        let binder = self.parse_binder()?;
+        consume_token!(self, TokenType::In)?;
+        let iter = self.restricted(Restrictions { no_struct_literal: true, ..Restrictions::default() }, 
+            Parser::parse_expression)?;
+        let stmts = self.parse_block()?;
+        Ok(self.finish(start, NewForExpression(token, binder, iter, stmts)))
     }
 
     /// Parses a do block based on the following rule:
@@ -706,7 +1637,19 @@ impl Parser {
     /// <doExpr>        ::= 'do' '{' [ <statement> ]* '}' ;
     /// 
     fn parse_do(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let tok = consume_token!(self, TokenType::Do)?;
+        let stmts = self.parse_block()?;
+        
+        Ok(self.finish(start, NewDoExpression(tok, stmts)))
+    }
+
+    /// Parses a block of statements based on the following rule:
+    /// 
+    /// <block>         ::= '{' [ <statement> ]* '}' ;
+    /// 
+    /// Returns a vector of nodes rather than a single node.
+    fn parse_block(&mut self) -> Result<Vec<Node>, ParserError> {
         consume_token!(self, TokenType::LCurly)?;
         let mut stmts = vec![];
         
@@ -717,7 +1660,9 @@ impl Parser {
                         self.next();
                         break;
                     },
-                    _ => {stmts.push(self.parse_statement()?)}
+                    _ => stmts.push(self.recover(Some(TokenType::RCurly), |p| {
+                        p.restricted(Restrictions::default(), Parser::parse_statement)
+                    })),
                 }
                 None => {
                     let (r, c) = self.get_last_token_location();
@@ -726,7 +1671,7 @@ impl Parser {
             }
         }
         
-        Ok(NewDoExpression(tok, stmts))
+        Ok(stmts)
     }
 
     /// Parses a return statement based on the following rule:
@@ -734,10 +1679,12 @@ impl Parser {
     /// <returnExpr>    ::= 'return' <expression> ;
     /// 
     fn parse_return(&mut self) -> Result<Node, ParserError> {
-        Ok(NewReturnExpression(consume_token!(
+        let start = self.span_start();
+        let node = NewReturnExpression(consume_token!(
             self, TokenType::Return)?, 
             self.parse_expression()?
-        ))
+        );
+        Ok(self.finish(start, node))
     }
 
     /// Parses a function type pattern based on the following rule:
@@ -745,20 +1692,24 @@ impl Parser {
     /// <typeFn>    ::= <typeCmpst> [ '->' <typeCmpst> ]? ;
     /// 
     fn parse_type_fn(&mut self) -> Result<Node, ParserError> {
-        let mut expr = self.parse_type_cmpst()?;
-
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::Arrow => {
-                    self.next();
-                    NewTypeFn(expr, self.parse_type_cmpst()?, tok)
-                }
-                _ => break,
+        self.nested(|p| {
+            let start = p.span_start();
+            let mut expr = p.parse_type_cmpst()?;
+
+            while let Some(tok) = p.peek() {
+                let op = match tok.typ {
+                    TokenType::Arrow => {
+                        p.next();
+                        let rhs = p.parse_type_cmpst()?;
+                        p.finish(start, NewTypeFn(expr, rhs, tok))
+                    }
+                    _ => break,
+                };
+                expr = op;
             };
-            expr = op;
-        };
 
-        Ok(expr)
+            Ok(expr)
+        })
     }
 
     /// Parses a tuple type pattern based on the following rule:
@@ -766,18 +1717,17 @@ impl Parser {
     /// <typeCmpst> ::= <typeLst> | <symbol> '<' [ <typeLst> [ ',' <typeLst> ]* ] '>' ;
     /// 
     fn parse_type_cmpst(&mut self) -> Result<Node, ParserError> {
-        // println!("current token: {}", self.peek().unwrap());
         if let Some(tok) = self.peek_twice() {
             if tok.typ == TokenType::Less {
-                
+                let start = self.span_start();
                 let name = self.parse_symbol()?;
                 consume_token!(self, TokenType::Less)?;
                 let mut types = Vec::new();
                 let mut first = true;
-                while let Some(tok2) = self.peek() {
-                    match tok2.typ {
-                        TokenType::Greater => { break; }
-                        _ => {
+                while self.peek().is_some() {
+                    match self.at_closing_angle() {
+                        true => { break; }
+                        false => {
                             if !first {
                                 consume_token!(self, TokenType::Comma)?;
                             }
@@ -788,7 +1738,7 @@ impl Parser {
                 };
 
                 consume_token!(self, TokenType::Greater)?;
-                return Ok(NewTypeCmpst(name, types))
+                return Ok(self.finish(start, NewTypeCmpst(name, types)))
             }
         };
 
@@ -802,10 +1752,11 @@ impl Parser {
     fn parse_type_lst(&mut self) -> Result<Node, ParserError> {
         if let Some(tok) = self.peek() {
             if tok.typ == TokenType::LBracket {
+                let start = self.span_start();
                 let token = consume_token!(self, TokenType::LBracket)?;
                 let ttype = self.parse_type_fn()?;
                 consume_token!(self, TokenType::RBracket)?;
-                return Ok(NewTypeLst(ttype, token));
+                return Ok(self.finish(start, NewTypeLst(ttype, token)));
             }
         }
         self.parse_type_tuple()
@@ -818,6 +1769,7 @@ impl Parser {
     fn parse_type_tuple(&mut self) -> Result<Node, ParserError> {
         if let Some(tok) = self.peek() {
             if tok.typ == TokenType::LParen {
+                let start = self.span_start();
                 consume_token!(self, TokenType::LParen)?;
 
                 let mut types = Vec::new();
@@ -834,7 +1786,7 @@ impl Parser {
                 };
 
                 consume_token!(self, TokenType::RParen)?;
-                return Ok(NewTypeTuple(types))
+                return Ok(self.finish(start, NewTypeTuple(types)))
             }
         };
 
@@ -846,14 +1798,17 @@ impl Parser {
     /// <type>      ::= [ 'mut' ]?  [ <symbol> | <typeFn> ] ;
     /// 
     fn parse_type(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         if let Some(tok) = self.peek() {
             match tok.typ {
                 TokenType::Mut => {
                     consume_token!(self, TokenType::Mut)?;
-                    Ok(NewTtype(self.parse_symbol()?, true))
+                    let symbol = self.parse_symbol()?;
+                    Ok(self.finish(start, NewTtype(symbol, true)))
                 },
                 TokenType::Symbol(_) => {
-                    Ok(NewTtype(self.parse_symbol()?, false))
+                    let symbol = self.parse_symbol()?;
+                    Ok(self.finish(start, NewTtype(symbol, false)))
                 },
                 _ => {
                     Err(new_parser_expected_one_of(tok.row, tok.col, vec![
@@ -863,226 +1818,199 @@ impl Parser {
             }
         } else {
             let (r, c) = self.get_last_token_location();
-            return Err(new_parser_expected(r, c, "`}`".to_string()))
-        }
-    }
-
-    /// Parses an and expression based on the following rule:
-    /// 
-    /// <or> ::= <and> [ '||' <and> ]* ;
-    /// 
-    fn parse_or(&mut self) -> Result<Node, ParserError> {
-        let mut expr = self.parse_and()?;
-
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::Or => {
-                    self.next();
-                    NewBinaryExpression(tok, expr, self.parse_and()?)
-                }
-                _ => break,
-            };
-            expr = op;
-        }
-
-        Ok(expr)
-    }
-
-    /// Parses an and expression based on the following rule:
-    /// 
-    /// <and> ::= <equality> [ '&&' <equality> ]* ;
-    /// 
-    fn parse_and(&mut self) -> Result<Node, ParserError> {
-        let mut expr = self.parse_equality()?;
-
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::And => {
-                    self.next();
-                    NewBinaryExpression(tok, expr, self.parse_equality()?)
-                }
-                _ => break,
-            };
-            expr = op;
-        }
-
-        Ok(expr)
-    }
-
-    /// Parses an equality expression based on the following rule:
-    /// 
-    /// <equality>      ::= <comparison> [ [ '==' | '!=' ] <comparison> ]* ;
-    /// 
-    fn parse_equality(&mut self) -> Result<Node, ParserError> {
-        let mut expr = self.parse_comparison()?;
-
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::Eq | TokenType::NotEq => {
-                    self.next();
-                    NewBinaryExpression(tok, expr, self.parse_comparison()?)
-                } 
-                _ => break,
-            };
-            expr = op;
-        }
-
-        Ok(expr)
-    }
-
-    /// Parses a comparison expression based on the following rule:
-    /// 
-    /// <comparison> ::= <addition> [ [ '>' 
-    ///                              | '>=' 
-    ///                              | '<' 
-    ///                              | '<=' ] <addition> ]* ;
-    /// 
-    fn parse_comparison(&mut self) -> Result<Node, ParserError> {
-        let mut expr = self.parse_addition()?;
-
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::Greater | TokenType::GreaterEqual
-                | TokenType::Less |TokenType::LessEqual  => {
-                    self.next();
-                    NewBinaryExpression(tok, expr, self.parse_addition()?)
-                } 
-                _ => break,
-            };
-            expr = op;
-        }
-
-        Ok(expr)
-    }
-
-    /// Parses an addition expression based on the following rule:
-    /// 
-    /// <addition> ::= <mult> [ ( '+' | '-' ) <mult> ]* ;
-    /// 
-    fn parse_addition(&mut self) -> Result<Node, ParserError> {
-        let mut expr = self.parse_mult()?;
-
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::Plus | TokenType::Minus => {
-                    self.next();
-                    NewBinaryExpression(tok, expr, self.parse_mult()?)
-                }
-                _ => break,
-            };
-            expr = op;
-        }
-
-        Ok(expr)
-    }
-
-    /// Parses a multiplication expression based on the following rule:
-    /// 
-    /// <mult> ::= <unary> [ ( '*' | '/' ) <unary> ]* ;
-    /// 
-    fn parse_mult(&mut self) -> Result<Node, ParserError> {
-        let mut expr = self.parse_unary()?;
-
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::Star | TokenType::Slash => {
-                    self.next();
-                    NewBinaryExpression(tok, expr, self.parse_unary()?)
-                } 
-                _ => break,
-            };
-            expr = op;
+            Err(new_parser_expected(r, c, "`}`".to_string()))
         }
-
-        Ok(expr)
     }
 
-    /// Parses a unary expression based on the following rule:
+    /// Parses an operator expression based on the following rule:
     /// 
-    /// <unary> ::= [ '!' | '-' ] <callExpr> | <callExpr> ;
+    /// <or> ::= <operand> [ <infixOp> <operand> ]* ;
     /// 
-    fn parse_unary(&mut self) -> Result<Node, ParserError> {
-        if let Some(tok) = self.peek() {
-            match tok.typ {
-                TokenType::Not | TokenType::Minus  => {
-                    let token = consume_token!(self, TokenType::Not)?;
-                    Ok(NewUnaryExpression(token, self.parse_call()?))
+    fn parse_or(&mut self) -> Result<Node, ParserError> {
+        self.parse_expr_bp(0)
+    }
+
+    /// Parses an operator expression with the Pratt algorithm, consuming 
+    /// every operator whose binding power is greater than `min_bp`. The 
+    /// operators themselves are looked up in `self.operators`.
+    /// 
+    /// Infix operators still waiting for their right operand are kept on an
+    /// explicit stack rather than in recursive calls, so a long chain such as
+    /// `a ** b ** ... ** z` doesn't use up the stack while it's parsed. Each 
+    /// of them still counts as a level of nesting until it's reduced, so a 
+    /// right-nested chain is limited like nested brackets are, while a flat 
+    /// `a + b + ... + z` never holds more than one level.
+    fn parse_expr_bp(&mut self, min_bp: u8) -> Result<Node, ParserError> {
+        let depth = self.depth;
+        let mut pending: Vec<PendingOp> = Vec::new();
+        let mut start = self.span_start();
+        let mut lhs = self.parse_operand(min_bp)?;
+
+        loop {
+            let bp = match pending.last() {
+                Some(top) => top.rhs_bp(),
+                None => min_bp,
+            };
+            if let Some((tok, op)) = self.next_infix(bp)? {
+                self.next();
+                self.descend()?;
+                pending.push(PendingOp { start, lhs, tok, op });
+                start = self.span_start();
+                lhs = self.parse_operand(pending[pending.len() - 1].rhs_bp())?;
+                continue;
+            }
+
+            let top = match pending.pop() {
+                Some(top) => top,
+                None => {
+                    self.depth = depth;
+                    return Ok(lhs);
                 },
-                _ => self.parse_call()
+            };
+            self.depth -= 1;
+            if top.op.assoc == Assoc::None {
+                self.check_non_associative(&top.tok, top.op)?;
             }
-        } else { self.parse_call() }
+            lhs = match top.op.handler {
+                Handler::Pipe => NewPipe(top.tok, top.lhs, lhs),
+                Handler::Compose => NewCompose(top.tok, top.lhs, lhs),
+                Handler::Range => NewRange(top.tok, top.lhs, lhs),
+                _ => NewBinaryExpression(top.tok, top.lhs, lhs),
+            };
+            lhs = self.finish(top.start, lhs);
+            start = top.start;
+        }
+    }
+
+    /// Returns the infix operator at the cursor, if there is one that binds 
+    /// tighter than `min_bp`. A user-defined operator without a fixity 
+    /// declaration is an error. Outside of comprehension clauses (see 
+    /// `is_comprehension`) a `<-` can't follow an operand, so `a<-1` is read
+    /// as `a < -1`.
+    fn next_infix(&mut self, min_bp: u8) -> Result<Option<(Token, Operator)>, ParserError> {
+        let tok = match self.peek() {
+            Some(tok) if tok.typ == TokenType::LeftArrow => {
+                self.split_token(TokenType::Less, TokenType::Minus);
+                self.peek().unwrap()
+            },
+            Some(tok) => tok,
+            None => return Ok(None),
+        };
+        if self.breaks_line(&tok) { return Ok(None) }
+        let op = match (self.operators.infix(&tok.typ), &tok.typ) {
+            (Some(op), _) => op,
+            (None, TokenType::Operator(name)) => {
+                return Err(new_parser_undeclared_operator(tok.row, tok.col, name.clone()))
+            },
+            (None, _) => return Ok(None),
+        };
+        if op.bp <= min_bp { return Ok(None) }
+        if tok.typ == TokenType::Bar && self.restrictions.no_bar { return Ok(None) }
+        Ok(Some((tok, op)))
     }
 
-    /// Parses an expression based on the following pattern:
+    /// Parses a single operand of an operator expression, along with its 
+    /// postfix operators, based on the following rule:
     /// 
-    /// <callExpr> ::= <accessAttr> | <accessAttr> <tuple> ;
+    /// <operand> ::= [ <prefixOp> ]* <factor> [ <postfixOp> ]* ;
     /// 
-    fn parse_call(&mut self) -> Result<Node, ParserError> {
-        if let Some(tok) = self.peek() {
-            match tok.typ {
-                TokenType::Symbol(_) => {
-                    if let Some(tok2) = self.peek_twice() {
-                        if tok2.typ == TokenType::LParen {
-                            let symbol = self.parse_symbol()?;
-                            let args = self.parse_tuple_vec()?;
-                            return Ok(NewCall(symbol, args))
-                        }
-                    }
-                }
-                _ => {}
+    fn parse_operand(&mut self, min_bp: u8) -> Result<Node, ParserError> {
+        self.nested(|p| {
+            let start = p.span_start();
+            let mut lhs = p.parse_prefix()?;
+
+            while let Some(tok) = p.peek() {
+                if p.breaks_line(&tok) { break }
+                let op = match p.operators.postfix(&tok.typ) {
+                    Some(op) if op.bp > min_bp => op,
+                    _ => break,
+                };
+                lhs = match op.handler {
+                    Handler::Call if is_path(&lhs) && p.is_named_fields() => {
+                        let token = consume_token!(p, TokenType::LParen)?;
+                        let fields = p.parse_field_inits(TokenType::Colon, TokenType::RParen)?;
+                        consume_token!(p, TokenType::RParen)?;
+                        NewStructLiteral(token, lhs, fields)
+                    },
+                    Handler::Call => {
+                        let args = p.parse_tuple_vec()?;
+                        NewCall(lhs, args)
+                    },
+                    Handler::Struct => {
+                        if p.restrictions.no_struct_literal || !is_path(&lhs) { break }
+                        let token = consume_token!(p, TokenType::LCurly)?;
+                        let fields = p.parse_field_inits(TokenType::Bind, TokenType::RCurly)?;
+                        consume_token!(p, TokenType::RCurly)?;
+                        NewStructLiteral(token, lhs, fields)
+                    },
+                    Handler::Index => {
+                        p.next();
+                        let index = p.restricted(
+                            Restrictions { ignore_newlines: true, ..Restrictions::default() }, 
+                            Parser::parse_or)?;
+                        consume_token!(p, TokenType::RBracket)?;
+                        NewAccessIndex(lhs, index)
+                    },
+                    Handler::Access => {
+                        p.next();
+                        let symbol = p.parse_symbol()?;
+                        NewAccess(lhs, symbol)
+                    },
+                    _ => break,
+                };
+                lhs = p.finish(start, lhs);
             }
-        };
 
-        self.parse_access_attr()
+            Ok(lhs)
+        })
     }
 
-    /// Parses an access attribute expression based on the following pattern:
-    /// 
-    /// <accessAttr>    ::= <accessIndx> [ '.' <callExpr> ]* ;
-    /// 
-    fn parse_access_attr(&mut self) -> Result<Node, ParserError> {
-        let mut expr = self.parse_access_indx()?;
+    /// Returns whether, in ASI mode, the line break before `tok` ends the 
+    /// expression being parsed. A line that starts with a token that can 
+    /// only continue an expression, such as `|>`, `+` or `.`, carries on the
+    /// previous one; a line that could start an expression of its own, with 
+    /// `(`, `[`, `{` or a prefix operator like `-`, doesn't.
+    fn breaks_line(&self, tok: &Token) -> bool {
+        self.options.asi && tok.newline && !self.restrictions.ignore_newlines 
+            && (self.operators.prefix(&tok.typ).is_some() 
+                || matches!(tok.typ, TokenType::LParen | TokenType::LBracket | TokenType::LCurly))
+    }
 
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::Dot => {
-                    self.next();
-                    NewAccess(expr, self.parse_call()?)
+    /// Non-associative operators cannot be chained with another operator of 
+    /// the same binding power, so `a == b == c` needs parentheses.
+    fn check_non_associative(&self, tok: &Token, op: Operator) -> Result<(), ParserError> {
+        if let Some(next) = self.peek() {
+            if let Some(op2) = self.operators.infix(&next.typ) {
+                if op2.bp == op.bp {
+                    return Err(new_parser_non_associative(next.row, next.col, format!("{}", tok.typ)));
                 }
-                _ => break,
-            };
-            expr = op;
-        };
+            }
+        }
 
-        Ok(expr)
+        Ok(())
     }
 
-    /// Parses an access index expression based on the following pattern:
+    /// Parses a prefix operator application, or falls through to a factor:
     /// 
-    /// <accessIndx>    ::= <factor> [ '[' <or> ']' ]* ;
+    /// <prefix> ::= <prefixOp> <operand> | <factor> ;
     /// 
-    fn parse_access_indx(&mut self) -> Result<Node, ParserError> {
-        let mut lhs = self.parse_factor()?;
-
-        while let Some(tok) = self.peek() {
-            let op = match tok.typ {
-                TokenType::LBracket => {
-                    self.next();
-                    let r = NewAccessIndex(lhs, self.parse_or()?);
-                    consume_token!(self, TokenType::RBracket)?;
-                    r
-                }
-                _ => break,
-            };
-            lhs = op;
-        };
+    fn parse_prefix(&mut self) -> Result<Node, ParserError> {
+        if let Some(tok) = self.peek() {
+            if let Some(op) = self.operators.prefix(&tok.typ) {
+                let start = self.span_start();
+                self.next();
+                let rhs = self.parse_expr_bp(op.bp)?;
+                return Ok(self.finish(start, NewUnaryExpression(tok, rhs)));
+            }
+        }
 
-        Ok(lhs)
+        self.parse_factor()
     }
 
     /// Parses a factor expression based on the following rule:
     /// 
     /// <factor>    ::= '(' [ <or> ]? ')' 
+    ///               | <unit>
     ///               | <tuple> 
     ///               | <listExpr> 
     ///               | <atom> ;
@@ -1091,23 +2019,93 @@ impl Parser {
         if let Some(tok) = self.peek() {
             match tok.typ {
                 TokenType::LParen => {
+                    if let Some(tok2) = self.peek_twice() {
+                        if tok2.typ == TokenType::RParen { return self.parse_unit() }
+                    }
                     if self.is_tuple() { return self.parse_tuple() }
                     consume_token!(self, TokenType::LParen)?;
-                    let expr = self.parse_expression();
+                    let expr = self.parse_delimited()?;
                     consume_token!(self, TokenType::RParen)?;
-                    expr
+                    Ok(expr)
                 },
-                TokenType::Integer(_) | TokenType::Symbol(_)  | TokenType::String(_) => {
+                TokenType::Integer(_) | TokenType::Float(_)
+                | TokenType::Symbol(_) | TokenType::String(_)
+                | TokenType::Bool(_) | TokenType::None => {
                     self.next();
                     Ok(NewAtom(tok))
                 },
                 TokenType::LBracket => {
                     self.parse_list()
+                },
+                TokenType::LCurly => {
+                    self.parse_struct_update()
                 }
                 _ => Err(new_parser_expected(tok.row, tok.col, "<factor>".to_string()))
             }
         } else {
-            Err( new_parser_expected(0, 0, "".to_string()))
+            let (r, c) = self.get_last_token_location();
+            Err(new_parser_expected(r, c + 1, "<factor>".to_string()))
+        }
+    }
+
+    /// Parses a functional struct update based on the following rule:
+    /// 
+    /// <structUpdate> ::= '{' <expression> 'with' <fieldInits> '}' ;
+    /// 
+    fn parse_struct_update(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        consume_token!(self, TokenType::LCurly)?;
+        let base = self.parse_delimited()?;
+        let token = consume_token!(self, TokenType::With)?;
+        let fields = self.parse_field_inits(TokenType::Bind, TokenType::RCurly)?;
+        consume_token!(self, TokenType::RCurly)?;
+
+        Ok(self.finish(start, NewStructUpdate(token, base, fields)))
+    }
+
+    /// Parses the fields of a struct literal or update up to `close`, based 
+    /// on the following rules:
+    /// 
+    /// <fieldInits> ::= <fieldInit> [ ',' <fieldInit> ]* [ ',' ]? ;
+    /// <fieldInit>  ::= <symbol> [ <sep> <expression> ]? ;
+    /// 
+    /// `sep` is `:` between parentheses and `=` between braces. A field 
+    /// without a value is punned: `Point { x }` is `Point { x = x }`.
+    fn parse_field_inits(&mut self, sep: TokenType, close: TokenType) -> Result<Vec<Node>, ParserError> {
+        let mut fields = Vec::new();
+        while let Some(tok) = self.peek() {
+            if tok.typ == close { break }
+            if !fields.is_empty() {
+                consume_token!(self, TokenType::Comma)?;
+                match self.peek() {
+                    Some(tok2) if tok2.typ == close => break,
+                    _ => {},
+                };
+            }
+
+            let start = self.span_start();
+            let symbol = self.parse_symbol()?;
+            let value = match self.peek() {
+                Some(tok2) if tok2.typ == sep => {
+                    self.next();
+                    Some(self.parse_delimited()?)
+                },
+                _ => None,
+            };
+            fields.push(self.finish(start, NewFieldInit(symbol, value)));
+        };
+
+        Ok(fields)
+    }
+
+    /// Determines if the `(` at the cursor opens named struct fields, as in
+    /// `Point(x: 1)`, rather than call arguments.
+    fn is_named_fields(&mut self) -> bool {
+        match (self.peek_twice(), self.peek_thrice()) {
+            (Some(tok), Some(tok2)) => {
+                matches!(tok.typ, TokenType::Symbol(_)) && tok2.typ == TokenType::Colon
+            },
+            _ => false,
         }
     }
 
@@ -1125,7 +2123,7 @@ impl Parser {
             cursor_temp += 1;
         };
 
-        false || first
+        first
     }
 
     /// Parses a tuple expression based on the following pattern:
@@ -1133,6 +2131,7 @@ impl Parser {
     /// <tuple> ::= '(' <expression> [ ',' <expression> ]* ')' ;
     /// 
     fn parse_tuple(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
         let mut exprs = Vec::new();
         let mut first = true;
 
@@ -1142,14 +2141,14 @@ impl Parser {
                 TokenType::RParen => { break },
                 _ => {
                     if !first { consume_token!(self, TokenType::Comma)?; }
-                    exprs.push(self.parse_expression()?);
+                    exprs.push(self.parse_delimited()?);
                     first = false;
                 }
             };
         };
         consume_token!(self, TokenType::RParen)?;
         
-        Ok(NewTupleAny(exprs))
+        Ok(self.finish(start, NewTupleAny(exprs)))
     }
     /// Same as `parse_tuple` but returns a vector of nodes rather than a 
     /// single node.
@@ -1163,7 +2162,7 @@ impl Parser {
                 TokenType::RParen => { break },
                 _ => {
                     if !first { consume_token!(self, TokenType::Comma)?; }
-                    exprs.push(self.parse_expression()?);
+                    exprs.push(self.parse_delimited()?);
                     first = false;
                 }
             };
@@ -1173,43 +2172,183 @@ impl Parser {
         Ok(exprs)
     }
 
+    /// Parses the unit value based on the following rule:
+    /// 
+    /// <unit> ::= '(' ')' ;
+    /// 
+    fn parse_unit(&mut self) -> Result<Node, ParserError> {
+        let start = self.span_start();
+        let token = consume_token!(self, TokenType::LParen)?;
+        consume_token!(self, TokenType::RParen)?;
+
+        Ok(self.finish(start, NewUnit(token)))
+    }
+
     fn parse_symbol(&mut self) -> Result<Node, ParserError> {
-        if let Some(tok) = self.next() {
+        if let Some(tok) = self.peek() {
             match tok.typ {
-                TokenType::Symbol(_) => Ok(NewAtom(tok)),
+                TokenType::Symbol(_) => {
+                    self.next();
+                    Ok(NewAtom(tok))
+                },
                 _ => Err(new_parser_expected(tok.row, tok.col, "<symbol>".to_string()))
             }
         } else {
             let (r, c) = self.get_last_token_location();
-            return Err(new_parser_expected(r, c, "<symbol>".to_string()))
+            Err(new_parser_expected(r, c, "<symbol>".to_string()))
+        }
+    }
+
+    fn parse_operator(&mut self) -> Result<Node, ParserError> {
+        if let Some(tok) = self.peek() {
+            match tok.typ {
+                TokenType::Operator(_) => {
+                    self.next();
+                    Ok(NewAtom(tok))
+                },
+                _ => Err(new_parser_expected(tok.row, tok.col, "<operator>".to_string()))
+            }
+        } else {
+            let (r, c) = self.get_last_token_location();
+            Err(new_parser_expected(r, c, "<operator>".to_string()))
         }
     }
 
     fn parse_string(&mut self) -> Result<Node, ParserError> {
-        if let Some(tok) = self.next() {
+        if let Some(tok) = self.peek() {
             match tok.typ {
-                TokenType::String(_) => Ok(NewAtom(tok)),
+                TokenType::String(_) => {
+                    self.next();
+                    Ok(NewAtom(tok))
+                },
                 _ => Err(new_parser_expected(tok.row, tok.col, "<string>".to_string()))
             }
         } else {
             let (r, c) = self.get_last_token_location();
-            return Err(new_parser_expected(r, c, "<string>".to_string()))
+            Err(new_parser_expected(r, c, "<string>".to_string()))
         }
     }
 
     fn parse_integer(&mut self) -> Result<Node, ParserError> {
-        if let Some(tok) = self.next() {
+        if let Some(tok) = self.peek() {
             match tok.typ {
-                TokenType::Integer(_) => Ok(NewAtom(tok)),
+                TokenType::Integer(_) => {
+                    self.next();
+                    Ok(NewAtom(tok))
+                },
                 _ => Err(new_parser_expected(tok.row, tok.col, "<integer>".to_string()))
             }
         } else {
             let (r, c) = self.get_last_token_location();
-            return Err(new_parser_expected(r, c, "<integer>".to_string()))
+            Err(new_parser_expected(r, c, "<integer>".to_string()))
         
         }
     }
 
+    /// Returns whether `target` appears between `from` and the end of the 
+    /// current list item, skipping over anything in nested brackets. The item
+    /// ends at a `,` or the closing bracket.
+    fn item_contains(&self, from: usize, target: &TokenType) -> bool {
+        let mut depth = 0;
+        for tok in self.tokens.iter().skip(from) {
+            match tok.typ {
+                TokenType::LParen | TokenType::LBracket | TokenType::LCurly => depth += 1,
+                TokenType::RParen | TokenType::RBracket | TokenType::RCurly => {
+                    if depth == 0 { return false; }
+                    depth -= 1;
+                },
+                TokenType::Comma if depth == 0 => return false,
+                ref typ if depth == 0 && typ == target => return true,
+                _ => {},
+            };
+        }
+
+        false
+    }
+
+    /// Returns whether the list whose items start at `from` is a 
+    /// comprehension, that is whether a `<-` follows a `|` outside of any
+    /// nested brackets. This is the one place a `<-` is taken to be the 
+    /// generator arrow: in a comprehension clause it is one, and anywhere
+    /// else `next_infix` reads it as `<` followed by a negation.
+    fn is_comprehension(&self, from: usize) -> bool {
+        let mut depth = 0;
+        let mut clauses = false;
+        for tok in self.tokens.iter().skip(from) {
+            match tok.typ {
+                TokenType::LParen | TokenType::LBracket | TokenType::LCurly => depth += 1,
+                TokenType::RParen | TokenType::RBracket | TokenType::RCurly => {
+                    if depth == 0 { return false; }
+                    depth -= 1;
+                },
+                TokenType::Bar if depth == 0 => clauses = true,
+                TokenType::LeftArrow if depth == 0 && clauses => return true,
+                _ => {},
+            };
+        }
+
+        false
+    }
+
+    /// Returns whether the next token closes a list of generics. Operator 
+    /// tokens starting with `>`, like the `>>` in `List<List<Int>>`, are 
+    /// split so that their first `>` becomes a token of its own.
+    fn at_closing_angle(&mut self) -> bool {
+        let text = match self.peek() {
+            Some(tok) => match tok.typ {
+                TokenType::Greater => return true,
+                _ => tok.typ.to_string(),
+            },
+            None => return false,
+        };
+        if !text.starts_with('>') {
+            return false;
+        }
+
+        self.split_token(TokenType::Greater, operator_token(&text[1..]));
+        true
+    }
+
+    /// Splits the token at the cursor after its first character, into a 
+    /// `first` token followed by a `rest` token.
+    fn split_token(&mut self, first: TokenType, rest: TokenType) {
+        let tok = &mut self.tokens[self.cursor];
+        let mut second = copy_token(tok);
+        second.typ = rest;
+        second.col += 1;
+        second.span.start += 1;
+        second.leading = Vec::new();
+        second.newline = false;
+        tok.typ = first;
+        tok.span.end = tok.span.start + 1;
+        tok.trailing = Vec::new();
+        self.tokens.insert(self.cursor + 1, second);
+    }
+
+    /// Returns the span of the next token, which is where a node about to be
+    /// parsed starts.
+    fn span_start(&self) -> Span {
+        match self.tokens.get(self.cursor) {
+            Some(tok) => tok.span,
+            None => self.prev_span(),
+        }
+    }
+
+    /// Returns the span of the last consumed token.
+    fn prev_span(&self) -> Span {
+        match self.cursor {
+            0 => Span::default(),
+            _ => self.tokens[self.cursor - 1].span,
+        }
+    }
+
+    /// Sets the span of `node` to run from `start` up to the end of the last
+    /// consumed token.
+    fn finish(&self, start: Span, mut node: Node) -> Node {
+        *node.span_mut() = start.to(self.prev_span());
+        node
+    }
+
     /// Next returns an optional token and advances the cursor if there is
     /// Some() next token.
     fn next(&mut self) -> Option<Token> {
@@ -1229,20 +2368,25 @@ impl Parser {
     }
 
     pub fn peek_twice(&mut self) -> Option<Token> {
-        if !(self.cursor + 1 >= self.tokens.len()) {
+        if self.cursor + 1 < self.tokens.len() {
             Some(copy_token(&self.tokens[self.cursor + 1]))
         } else { None }
     }
 
     // Wait is thrice a word? lol
     pub fn peek_thrice(&mut self) -> Option<Token> {
-        if !(self.cursor + 2 >= self.tokens.len()) {
+        if self.cursor + 2 < self.tokens.len() {
             Some(copy_token(&self.tokens[self.cursor + 2]))
         } else { None }
     }
 
+    /// Returns the location of the last token consumed. Before any token 
+    /// has been consumed, this is the column before the start of the input.
     fn get_last_token_location(&self) -> (usize, usize) {
-        (self.tokens[self.cursor - 1].row, self.tokens[self.cursor - 1].col)
+        match self.cursor.checked_sub(1).and_then(|idx| self.tokens.get(idx)) {
+            Some(tok) => (tok.row, tok.col),
+            None => (1, 0),
+        }
     }
 
     //// Returns whether we are at the end of the token stream.
@@ -1251,14 +2395,44 @@ impl Parser {
     }
 }
 
+/// Returns whether `node` is a symbol or a chain of accesses on one, such as
+/// `geo.Point`, which is all a struct literal accepts as its name.
+fn is_path(node: &Node) -> bool {
+    match node {
+        Node::Atom(atom) => matches!(atom.token.typ, TokenType::Symbol(_)),
+        Node::Access(access) => is_path(&access.lhs),
+        _ => false,
+    }
+}
+
+/// Returns whether `typ`, a declaration keyword, can also start an 
+/// expression nested in brackets, as in `map(xs, fn (x) => x)`.
+fn starts_expression(typ: &TokenType) -> bool {
+    matches!(typ, TokenType::Let | TokenType::Fn | TokenType::Struct)
+}
+
+/// Returns whether `typ` starts a declaration, which makes it a safe place to
+/// resume parsing after an error.
+fn is_declaration(typ: &TokenType) -> bool {
+    matches!(typ, 
+        TokenType::Let | TokenType::Fn | TokenType::Pub | TokenType::Data
+        | TokenType::Type | TokenType::Newtype
+        | TokenType::Struct | TokenType::Trait | TokenType::Impl 
+        | TokenType::Package | TokenType::Import | TokenType::Infixl 
+        | TokenType::Infixr | TokenType::Infix | TokenType::Decorator(_))
+}
+
+
 #[macro_export]
-/// expands into code that consumes a TokenType.
+/// expands into code that consumes a TokenType. Any other token is left in 
+/// place, so error recovery starts from it.
 macro_rules! consume_token {
     ($self:ident, $token:path) => {
-        match $self.next() {
+        match $self.peek() {
             Some(tok) => {
                 match tok.typ {
                     $token => {
+                        $self.next();
                         $self.last = Some((tok.row, tok.col));
                         Ok(tok)
                     },
@@ -1273,3 +2447,529 @@ macro_rules! consume_token {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::lexer::lex_file;
+
+    /// Renders `node` as an s-expression, which keeps the expected trees in
+    /// the tests below short.
+    fn show(node: &Node) -> String {
+        match node {
+            Node::Root(node) => show_all(&node.children),
+            Node::Atom(node) => match &node.token.typ {
+                TokenType::String(s) => format!("{s:?}"),
+                TokenType::Symbol(s) | TokenType::Operator(s) => s.clone(),
+                typ => typ.to_string().rsplit(": ").next().unwrap().to_string(),
+            },
+            Node::Unit(_) => "()".to_string(),
+            Node::Error(_) => "<error>".to_string(),
+            Node::LetExpr(node) => format!("(let {} {})", show_all(&node.symbols), show(&node.rhs)),
+            Node::FnSignature(node) => format!("(fn {} :: {})", show(&node.symbol), show(&node.ttype)),
+            Node::FnDeclaration(node) => {
+                format!("(fn {} ({}) {})", show(&node.symbol), show_all(&node.arguments), show(&node.rhs))
+            },
+            Node::FnAnon(node) => format!("(fn ({}) {})", show_all(&node.arguments), show(&node.rhs)),
+            Node::BinaryExpression(node) => {
+                format!("({} {} {})", node.token.typ, show(&node.lhs), show(&node.rhs))
+            },
+            Node::UnaryExpression(node) => format!("({} {})", node.token.typ, show(&node.rhs)),
+            Node::Pipe(node) => format!("(|> {} {})", show(&node.lhs), show(&node.rhs)),
+            Node::Compose(node) => format!("({} {} {})", node.token.typ, show(&node.lhs), show(&node.rhs)),
+            Node::Call(node) => format!("(call {})", show_all_with(&node.lhs, &node.args)),
+            Node::Access(node) => format!("(. {} {})", show(&node.lhs), show(&node.rhs)),
+            Node::AccessIndex(node) => format!("([] {} {})", show(&node.symbol), show(&node.index)),
+            Node::TupleAny(node) => format!("(tuple {})", show_all(&node.items)),
+            Node::StructLiteral(node) => format!("(struct {} [{}])", show(&node.name), show_all(&node.fields)),
+            Node::StructUpdate(node) => format!("(with {} [{}])", show(&node.base), show_all(&node.fields)),
+            Node::FieldInit(node) => match node.value.as_ref() {
+                Some(value) => format!("({} {})", show(&node.symbol), show(value)),
+                None => show(&node.symbol),
+            },
+            Node::ListLiteral(node) => format!("[{}]", show_all(&node.items)),
+            Node::ListSplit(node) => format!("[{} | {}]", show(&node.head), show(&node.tail)),
+            Node::ListComprehension(node) => format!("[{} | {}]", show(&node.expr), show_all(&node.clauses)),
+            Node::Generator(node) => format!("(<- {} {})", show(&node.pattern), show(&node.iter)),
+            Node::Range(node) => format!("({} {} {})", node.token.typ, show(&node.lhs), show(&node.rhs)),
+            Node::TraitDeclaration(node) => {
+                format!("(trait {} [{}])", show(&node.symbol), show_all(&node.methods))
+            },
+            Node::ImplBlock(node) => match node.ttrait.as_ref() {
+                Some(ttrait) => format!("(impl {} for {} [{}])", 
+                    show(ttrait), show(&node.ttype), show_all(&node.items)),
+                None => format!("(impl {} [{}])", show(&node.ttype), show_all(&node.items)),
+            },
+            Node::ForExpression(node) => format!("(for [{}] {} [{}])", 
+                show_all(&node.binder), show(&node.iter), show_all(&node.statements)),
+            Node::FixityDeclaration(node) => format!("({} {} {})", 
+                node.token.typ, show(&node.level), show_all(&node.operators)),
+            Node::WildcardPattern(_) => "_".to_string(),
+            Node::ConstructorPattern(node) => format!("({})", show_all_with(&node.name, &node.args)),
+            Node::TuplePattern(node) => format!("(tuple {})", show_all(&node.items)),
+            Node::ListPattern(node) => match node.rest.as_ref() {
+                Some(rest) => format!("[{} | {}]", show_all(&node.items), show(rest)),
+                None => format!("[{}]", show_all(&node.items)),
+            },
+            Node::TypeFn(node) => format!("(-> {} {})", show(&node.lhs), show(&node.rhs)),
+            Node::TypeLst(node) => format!("[{}]", show(&node.ttype)),
+            Node::TypeTuple(node) => format!("(tuple {})", show_all(&node.ttypes)),
+            Node::TypeCmpst(node) if node.items.is_empty() => show(&node.ttype),
+            Node::TypeCmpst(node) => format!("({} {})", show(&node.ttype), show_all(&node.items)),
+            Node::GenericParam(node) if node.bounds.is_empty() => show(&node.symbol),
+            Node::GenericParam(node) => format!("(: {} {})", show(&node.symbol), show_all(&node.bounds)),
+            Node::TypeAlias(node) => format!("(type {} [{}] {})", 
+                show(&node.symbol), show_all(&node.generics), show(&node.ttype)),
+            Node::Newtype(node) => format!("(newtype {} [{}] {})", 
+                show(&node.symbol), show_all(&node.generics), show(&node.ttype)),
+            Node::PubExpr(node) => format!("(pub {})", show(&node.rhs)),
+            Node::Import(node) => {
+                let mut out = format!("(import {}", show_all(&node.rhs));
+                if let Some(alias) = node.alias.as_ref() { out += &format!(" as {}", show(alias)) }
+                if !node.items.is_empty() { out += &format!(" [{}]", show_all(&node.items)) }
+                if !node.hiding.is_empty() { out += &format!(" hiding [{}]", show_all(&node.hiding)) }
+                out + ")"
+            },
+            Node::Ttype(node) => show(&node.symbol),
+            _ => "<?>".to_string(),
+        }
+    }
+
+    fn show_all(nodes: &[Node]) -> String {
+        nodes.iter().map(show).collect::<Vec<_>>().join(" ")
+    }
+
+    fn show_all_with(first: &Node, rest: &[Node]) -> String {
+        std::iter::once(first).chain(rest).map(show).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Parses a whole program, asserting that it has no errors.
+    fn program(src: &str) -> String {
+        let (tree, errors) = parse(lex(src).unwrap());
+        assert_eq!(errors, vec![]);
+        show(&tree)
+    }
+
+    fn expr(src: &str) -> String {
+        show(&parse_expr_str(src).unwrap())
+    }
+
+    /// Parses a whole program, returning its tree and every error found.
+    fn recovered(src: &str) -> (String, Vec<ParserError>) {
+        let (tree, errors) = parse(lex(src).unwrap());
+        (show(&tree), errors)
+    }
+
+    fn expected(row: usize, col: usize, what: &str) -> ParserError {
+        new_parser_expected(row, col, what.to_string())
+    }
+
+    #[test]
+    fn parses_a_lexed_program() {
+        let src = "
+            fn factorial :: Int -> Int;
+            fn factorial (0) => 1;
+            fn factorial (n) => n * factorial(n - 1);
+        ";
+        assert_eq!(program(src), "(fn factorial :: (-> Int Int)) \
+            (fn factorial (0) 1) \
+            (fn factorial (n) (* n (call factorial (- n 1))))");
+    }
+
+    #[test]
+    fn parses_literals() {
+        assert_eq!(expr("(true, false, None, (), 1.5, \"s\")"), "(tuple true false None () 1.5 \"s\")");
+        assert_eq!(expr("!true || None == ()"), "(|| (! true) (== None ()))");
+    }
+
+    fn span(node: &Node) -> (usize, usize) {
+        (node.span().start, node.span().end)
+    }
+
+    #[test]
+    fn spans_nodes_from_first_to_last_token() {
+        let node = parse_expr_str("foo(1, bar.baz) + (x)").unwrap();
+        assert_eq!(span(&node), (0, 21));
+        let Node::BinaryExpression(bin) = node else { panic!("expected a binary expression") };
+        assert_eq!(span(&bin.lhs), (0, 15));
+        let Node::Call(call) = *bin.lhs else { panic!("expected a call") };
+        assert_eq!(span(&call.args[1]), (7, 14));
+        assert_eq!(span(&bin.rhs), (19, 20));
+    }
+
+    #[test]
+    fn spans_the_root_and_error_nodes() {
+        let src = "let a = 1;\nlet b = ;\nlet c = 3;";
+        let (tree, errors) = parse(lex(src).unwrap());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(span(&tree), (0, 31));
+        let Node::Root(root) = tree else { panic!("expected the root") };
+        assert_eq!(span(&root.children[1]), (11, 20));
+        assert_eq!(root.children[1].span().line_col(src), ((2, 1), (2, 10)));
+    }
+
+    #[test]
+    fn parses_trait_declarations() {
+        assert_eq!(
+            program("trait Show ( fn show :: a -> String; fn debug(x) => show(x); );"),
+            "(trait Show [(fn show :: (-> a String)) (fn debug (x) (call show x))])",
+        );
+        assert_eq!(program("trait Empty ();"), "(trait Empty [])");
+    }
+
+    #[test]
+    fn parses_impl_blocks() {
+        assert_eq!(
+            program("impl Show for Point ( fn show(p) => p.x; );"),
+            "(impl Show for Point [(fn show (p) (. p x))])",
+        );
+        assert_eq!(
+            program("impl Point ( fn origin :: Point; fn norm(p) => 0; );"),
+            "(impl Point [(fn origin :: Point) (fn norm (p) 0)])",
+        );
+    }
+
+    #[test]
+    fn parses_for_loops() {
+        assert_eq!(
+            program("for x in xs { print(x); };"),
+            "(for [x] xs [(call print x)])",
+        );
+        assert_eq!(
+            program("for (k, v) in pairs(m) { let s = k; v; };"),
+            "(for [k v] (call pairs m) [(let s k) v])",
+        );
+    }
+
+    #[test]
+    fn attaches_decorators_to_declarations() {
+        let node = parse_statement_str("@inline @deprecated(\"use g\", 2) fn f(x) => x;").unwrap();
+        let Node::FnDeclaration(decl) = node else { panic!("expected a declaration") };
+        let names: Vec<&str> = decl.decorators.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["inline", "deprecated"]);
+        assert_eq!(show_all(&decl.decorators[1].args), "\"use g\" 2");
+        assert_eq!(
+            parse_statement_str("@inline let x = 1;").err(),
+            Some(SyntaxError::Parser(new_parser_expected(1, 9, "<declaration>".to_string()))),
+        );
+    }
+
+    /// Returns the decorator names of each method in an `impl` or `trait`.
+    fn method_decorators(src: &str) -> Vec<Vec<String>> {
+        let methods = match parse_statement_str(src).unwrap() {
+            Node::ImplBlock(node) => node.items,
+            Node::TraitDeclaration(node) => node.methods,
+            node => panic!("expected an impl or trait, got {}", show(&node)),
+        };
+        methods.iter().map(|method| {
+            let decorators = match method {
+                Node::FnDeclaration(decl) => &decl.decorators,
+                Node::FnSignature(sig) => &sig.decorators,
+                node => panic!("expected a method, got {}", show(node)),
+            };
+            decorators.iter().map(|d| d.name.clone()).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn attaches_decorators_to_methods() {
+        assert_eq!(
+            method_decorators("impl Point ( @inline fn norm(p) => 0; fn zero(p) => p; );"),
+            vec![vec!["inline".to_string()], vec![]],
+        );
+        assert_eq!(
+            method_decorators("trait Show ( @deprecated(\"use fmt\") @test fn show :: a -> String; );"),
+            vec![vec!["deprecated".to_string(), "test".to_string()]],
+        );
+        assert_eq!(
+            parse_statement_str("impl Point ( @inline let x = 1; );").err(),
+            Some(SyntaxError::Parser(expected(1, 22, "`fn`"))),
+        );
+    }
+
+    #[test]
+    fn follows_operator_precedence() {
+        assert_eq!(expr("1 + 2 * 3 - 4 / 5"), "(- (+ 1 (* 2 3)) (/ 4 5))");
+        assert_eq!(expr("a || b && c == d < e + 1"), "(|| a (&& b (== c (< d (+ e 1)))))");
+        assert_eq!(expr("(a + b) * c"), "(* (+ a b) c)");
+    }
+
+    #[test]
+    fn binds_prefix_and_postfix_operators() {
+        assert_eq!(expr("-a * !b"), "(* (- a) (! b))");
+        assert_eq!(expr("- - a"), "(- (- a))");
+        assert_eq!(expr("-f(x).y[0]"), "(- ([] (. (call f x) y) 0))");
+    }
+
+    #[test]
+    fn honors_fixity_declarations() {
+        let src = "
+            infixl 6 <+>;
+            infixr 5 ++;
+            let a = x <+> y <+> z * 2;
+            let b = xs ++ ys ++ zs;
+        ";
+        assert_eq!(program(src), "(infixl 6 <+>) (infixr 5 ++) \
+            (let a (<+> (<+> x y) (* z 2))) \
+            (let b (++ xs (++ ys zs)))");
+    }
+
+    #[test]
+    fn rejects_operators_without_a_fixity() {
+        let (_, errors) = parse(lex("let a = x <+> y;\ninfixl 6 <+>;\nlet b = x <+> y;").unwrap());
+        assert_eq!(errors, vec![new_parser_undeclared_operator(1, 11, "<+>".to_string())]);
+    }
+
+    #[test]
+    fn reads_a_left_arrow_after_an_operand_as_less_than_negative() {
+        assert_eq!(expr("a<-1"), "(< a (- 1))");
+        assert_eq!(expr("[a<-1, 2]"), "[(< a (- 1)) 2]");
+        assert_eq!(expr("[f(a<-1), [b<-2]]"), "[(call f (< a (- 1))) [(< b (- 2))]]");
+        assert_eq!(expr("[x | x<-1]"), "[x | (<- x 1)]");
+        assert_eq!(
+            show(&parse_statement_str("let x=-1==a*-b;").unwrap()), 
+            "(let x (== (- 1) (* a (- b))))",
+        );
+    }
+
+    #[test]
+    fn keeps_statements_missing_only_their_semicolon() {
+        let (tree, errors) = recovered("let a = 1\nfn f(x) => x +;\nlet c = 3;");
+        assert_eq!(tree, "(let a 1) <error> (let c 3)");
+        assert_eq!(errors, vec![expected(2, 1, ";"), expected(2, 15, "<factor>")]);
+    }
+
+    #[test]
+    fn skips_the_rest_of_the_bracket_an_error_is_in() {
+        let (tree, errors) = recovered("let a = foo(1 2, fn (x) => x);\nlet b = 2;");
+        assert_eq!(tree, "<error> (let b 2)");
+        assert_eq!(errors, vec![expected(1, 15, ",")]);
+    }
+
+    #[test]
+    fn stops_at_the_end_of_a_statement_left_in_an_open_bracket() {
+        let (tree, errors) = recovered("let a = foo(1, 2;\nlet b = bar(1 2,\ndata X ( A );\nlet c = 3;");
+        assert_eq!(tree, "<error> <error> <?> (let c 3)");
+        assert_eq!(errors, vec![expected(1, 17, ","), expected(2, 15, ",")]);
+    }
+
+    #[test]
+    fn recovers_inside_blocks_and_impls() {
+        let (tree, errors) = recovered("impl X ( fn a(x) => ; fn b(x) => x; );\ndo { 1 +; 2; };");
+        assert_eq!(tree, "(impl X [<error> (fn b (x) x)]) <?>");
+        assert_eq!(errors, vec![expected(1, 21, "<factor>"), expected(2, 9, "<factor>")]);
+    }
+
+    #[test]
+    fn parses_negative_number_patterns() {
+        let atom = |src| match parse_pattern_str(src).unwrap() {
+            Node::Atom(atom) => {
+                let span = (atom.span.start, atom.span.end);
+                (atom.token.typ, span)
+            },
+            node => panic!("expected an atom, got {}", show(&node)),
+        };
+        assert_eq!(atom("-1"), (TokenType::Integer(-1), (0, 2)));
+        assert_eq!(atom("- 2.5"), (TokenType::Float(-2.5), (0, 5)));
+        assert_eq!(parse_pattern_str("-x").err(), Some(SyntaxError::Parser(expected(1, 1, "<pattern>"))));
+        program("let sign = fn (n) => match n with ( | -1 -> 0 | [-1, 2.5 | _] -> 1 | _ -> n );");
+    }
+
+    #[test]
+    fn chains_calls_on_any_callee() {
+        assert_eq!(expr("f(x)(y)"), "(call (call f x) y)");
+        assert_eq!(expr("(fn (x) => x)(1)"), "(call (fn (x) x) 1)");
+        assert_eq!(expr("obj.method(1)(2)"), "(call (call (. obj method) 1) 2)");
+        assert_eq!(expr("list[0](x)"), "(call ([] list 0) x)");
+        assert_eq!(expr("a.b[0].c()"), "(call (. ([] (. a b) 0) c))");
+        assert_eq!(span(&parse_expr_str(" f(x)(y) ").unwrap()), (1, 8));
+    }
+
+    #[test]
+    fn parses_pipes_and_composition() {
+        assert_eq!(expr("xs |> map(f) |> filter(g)"), "(|> (|> xs (call map f)) (call filter g))");
+        assert_eq!(expr("f >> g >> h"), "(>> (>> f g) h)");
+        assert_eq!(expr("f << g"), "(<< f g)");
+        assert_eq!(expr("a || b |> f"), "(|> (|| a b) f)");
+        assert_eq!(expr("x |> f >> g"), "(|> x (>> f g))");
+        assert_eq!(span(&parse_expr_str("xs |> f").unwrap()), (0, 7));
+    }
+
+    #[test]
+    fn parses_arithmetic_and_bitwise_operators() {
+        assert_eq!(expr("a % b * c"), "(* (% a b) c)");
+        assert_eq!(expr("a ** b ** c"), "(** a (** b c))");
+        assert_eq!(expr("-a ** b"), "(- (** a b))");
+        assert_eq!(expr("a | b ^ c & d"), "(| a (^ b (& c d)))");
+        assert_eq!(expr("a & b == c"), "(== (& a b) c)");
+        assert_eq!(expr("~a <<< 2"), "(<<< (~ a) 2)");
+    }
+
+    #[test]
+    fn shifts_bind_tighter_than_composition() {
+        assert_eq!(expr("a <<< b << c"), "(<< (<<< a b) c)");
+        assert_eq!(expr("a >> b >>> c"), "(>> a (>>> b c))");
+        assert_eq!(expr("a <<< b + c"), "(<<< a (+ b c))");
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(expr("1..10"), "(.. 1 10)");
+        assert_eq!(expr("a..=b + 1"), "(..= a (+ b 1))");
+        assert_eq!(
+            parse_expr_str("1..2..3").err(),
+            Some(SyntaxError::Parser(new_parser_non_associative(1, 5, "..".to_string())))
+        );
+    }
+
+    #[test]
+    fn tells_comprehensions_from_list_splits() {
+        assert_eq!(expr("[h | t]"), "[h | t]");
+        assert_eq!(expr("[x * 2 | x <- xs, x > 0]"), "[(* x 2) | (<- x xs) (> x 0)]");
+        assert_eq!(expr("[(x, y) | x <- 1..3, y <- ys]"), "[(tuple x y) | (<- x (.. 1 3)) (<- y ys)]");
+        assert_eq!(expr("[a<-1, 2]"), "[(< a (- 1)) 2]");
+        assert_eq!(expr("[a<-1 | x <- xs]"), "[(< a (- 1)) | (<- x xs)]");
+        assert_eq!(expr("[[x | x <- xs], y<-2]"), "[[x | (<- x xs)] (< y (- 2))]");
+    }
+
+    #[test]
+    fn parses_struct_literals_and_updates() {
+        assert_eq!(expr("Point(x: 1, y: 2)"), "(struct Point [(x 1) (y 2)])");
+        assert_eq!(expr("geo.Point(x: 1)"), "(struct (. geo Point) [(x 1)])");
+        assert_eq!(expr("Point { x = 1, y }"), "(struct Point [(x 1) y])");
+        assert_eq!(expr("{ p with x = 3 }"), "(with p [(x 3)])");
+    }
+
+    #[test]
+    fn only_builds_struct_literals_on_paths() {
+        assert_eq!(
+            parse_expr_str("f(1)(x: 2)").err(),
+            Some(SyntaxError::Parser(expected(1, 7, ",")))
+        );
+        assert!(parse_expr_str("f(1) { x = 1 }").is_err());
+    }
+
+    /// Shows the generics and where clause of a declaration statement.
+    fn generics(src: &str) -> (String, String) {
+        match parse_statement_str(src).unwrap() {
+            Node::FnSignature(node) => (show_all(&node.generics), show_all(&node.where_clause)),
+            Node::FnDeclaration(node) => (show_all(&node.generics), show_all(&node.where_clause)),
+            Node::StructDeclaration(node) => (show_all(&node.generics), show_all(&node.where_clause)),
+            node => panic!("expected a declaration, got {}", show(&node)),
+        }
+    }
+
+    #[test]
+    fn parses_generics_and_where_clauses() {
+        assert_eq!(generics("fn id<a> :: a -> a;"), ("a".to_string(), "".to_string()));
+        assert_eq!(generics("fn g<a: Show + Eq>(x) => x;"), ("(: a Show Eq)".to_string(), "".to_string()));
+        assert_eq!(
+            generics("fn f :: a -> String where a: Show + Eq, b: Ord;"),
+            ("".to_string(), "(: a Show Eq) (: b Ord)".to_string())
+        );
+        assert_eq!(
+            generics("struct Pair<a, b> where a: Eq ( fst :: a, snd :: b );"),
+            ("a b".to_string(), "(: a Eq)".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_type_aliases_and_newtypes() {
+        assert_eq!(
+            program("type Parser<a> = String -> Option<(a, String)>;"),
+            "(type Parser [a] (-> String (Option (tuple a String))))"
+        );
+        assert_eq!(program("newtype UserId = Int;"), "(newtype UserId [] Int)");
+        assert_eq!(program("pub type Id = Int;"), "(pub (type Id [] Int))");
+        assert_eq!(program("pub newtype Name = String;"), "(pub (newtype Name [] String))");
+    }
+
+    #[test]
+    fn parses_selective_and_aliased_imports() {
+        assert_eq!(program(r#"import "net/http" as http;"#), r#"(import "net/http" as http)"#);
+        assert_eq!(program(r#"import "list" (map, filter,);"#), r#"(import "list" [map filter])"#);
+        assert_eq!(program(r#"import "list" hiding (head);"#), r#"(import "list" hiding [head])"#);
+        assert_eq!(program(r#"import "ops" (<+>, ++);"#), r#"(import "ops" [<+> ++])"#);
+        assert_eq!(program(r#"import ("a", "b");"#), r#"(import "a" "b")"#);
+        assert_eq!(program(r#"pub import "list" (map);"#), r#"(pub (import "list" [map]))"#);
+    }
+
+    /// Parses a whole program with automatic semicolon insertion.
+    fn asi(src: &str) -> String {
+        let options = ParserOptions { asi: true, ..ParserOptions::default() };
+        let (tree, errors) = parse_with(lex(src).unwrap(), options);
+        assert_eq!(errors, vec![]);
+        show(&tree)
+    }
+
+    #[test]
+    fn ends_complete_statements_at_line_breaks() {
+        assert_eq!(
+            asi("let a = 1\nlet b = a +\n  2\nlet c = f(\n  a,\n  b)"),
+            "(let a 1) (let b (+ a 2)) (let c (call f a b))"
+        );
+        assert_eq!(asi("let a = 1 (- spans\nlines -) let b = 2"), "(let a 1) (let b 2)");
+    }
+
+    #[test]
+    fn parses_fragments() {
+        assert_eq!(expr("f(x) + 1"), "(+ (call f x) 1)");
+        assert_eq!(show(&parse_type_str("Int -> [a]").unwrap()), "(-> Int [a])");
+        assert_eq!(show(&parse_statement_str("let x = 1;").unwrap()), "(let x 1)");
+        assert_eq!(show(&parse_pattern_str("Some((x, _))").unwrap()), "(Some (tuple x _))");
+        assert_eq!(show(&parse_pattern_str("[a, b | rest]").unwrap()), "[a b | rest]");
+    }
+
+    #[test]
+    fn fails_fragments_with_any_error() {
+        let err = |res: Result<Node, SyntaxError>| res.err().map(|err| err.location());
+        assert_eq!(err(parse_expr_str("1 + # 2")), Some((1, 5)));
+        assert_eq!(err(parse_expr_str("1 2")), Some((1, 3)));
+        assert_eq!(err(parse_expr_str("-")), Some((1, 2)));
+        assert_eq!(err(parse_expr_str("1 +")), Some((1, 4)));
+        assert_eq!(err(parse_statement_str("let x = 1")), Some((1, 10)));
+        assert_eq!(err(parse_expr_str("do { 1 + ; }")), Some((1, 10)));
+        assert_eq!(err(parse_expr_str("impl Show for X ( fn show(x) => ; )")), Some((1, 33)));
+    }
+
+    fn too_deep(res: Result<Node, SyntaxError>) -> bool {
+        let err = res.err().unwrap();
+        let (r, c) = err.location();
+        err == SyntaxError::Parser(new_parser_nesting_too_deep(r, c, DEFAULT_MAX_DEPTH))
+    }
+
+    #[test]
+    fn parses_long_flat_chains() {
+        let sum = vec!["1"; 300].join(" + ");
+        assert_eq!(span(&parse_expr_str(&sum).unwrap()), (0, sum.len()));
+        let calls = format!("x{}", ".m()".repeat(1000));
+        assert_eq!(span(&parse_expr_str(&calls).unwrap()), (0, calls.len()));
+        let mixed = format!("a{}", " * b + c.d(e)[f] - g".repeat(300));
+        assert!(parse_expr_str(&mixed).is_ok());
+    }
+
+    #[test]
+    fn limits_deep_nesting() {
+        // `DEFAULT_MAX_DEPTH` is sized for the main thread's stack, which is
+        // larger than a test thread's.
+        let limits = std::thread::Builder::new().stack_size(8 << 20).spawn(|| {
+            let n = 100_000;
+            assert!(too_deep(parse_expr_str(&format!("{}1{}", "(".repeat(n), ")".repeat(n)))));
+            assert!(too_deep(parse_expr_str(&format!("{}1", "- ".repeat(n)))));
+            assert!(too_deep(parse_expr_str(&vec!["2"; n].join(" ** "))));
+            assert!(too_deep(parse_expr_str(&format!("{}x", "f(".repeat(n)))));
+            assert!(too_deep(parse_pattern_str(&format!("{}_{}", "[".repeat(n), "]".repeat(n)))));
+        });
+        limits.unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn keeps_the_file_id_on_node_spans() {
+        let (tree, errors) = parse(lex_file("let x = f(1);", 7).unwrap());
+        assert_eq!(errors, vec![]);
+        let Node::Root(root) = tree else { panic!("expected a root") };
+        let Node::LetExpr(ref let_expr) = root.children[0] else { panic!("expected a let") };
+        assert_eq!(root.children[0].span(), Span::new(7, 0, 12));
+        assert_eq!(let_expr.rhs.span(), Span::new(7, 8, 12));
+    }
+}
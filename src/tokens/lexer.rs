@@ -0,0 +1,781 @@
+use crate::errors::errors::*;
+use crate::tokens::tokens::*;
+
+/// Lexer encapsulates the hand-written scanner for ivy source text.
+pub struct Lexer {
+    chars:  Vec<char>,
+    tokens: Vec<Token>,
+    trivia: Vec<Trivia>,
+    cursor: usize,
+    row:    usize,
+    col:    usize,
+    file:   usize,
+    offset: usize,
+    start:  usize,
+    newline: bool,
+}
+
+/// Lexes `src` as file 0.
+pub fn lex(src: &str) -> Result<Vec<Token>, LexerError> {
+    lex_file(src, 0)
+}
+
+/// Lexes `src`, tagging every span it produces with the given file id.
+pub fn lex_file(src: &str, file: usize) -> Result<Vec<Token>, LexerError> {
+    Lexer::new(src, file).lex()
+}
+
+impl Lexer {
+    /// Creates a lexer for `src`, tagging every span it produces with the
+    /// given file id.
+    pub fn new(src: &str, file: usize) -> Lexer {
+        Lexer {
+            chars: src.chars().collect(), 
+            tokens: Vec::new(), 
+            trivia: Vec::new(), 
+            cursor: 0, row: 1, col: 1,
+            file, offset: 0, start: 0,
+            newline: false,
+        }
+    }
+
+    fn lex(&mut self) -> Result<Vec<Token>, LexerError> {
+        while let Some(car) = self.peek() {
+            self.start = self.offset;
+            match car {
+                ' ' | '\t' | '\r'           => { self.next(); },
+                '\n'                        => { self.newline = true; self.next(); },
+                '"'                         => self.lex_string()?,
+                'r' if self.is_raw_string() => self.lex_raw_string()?,
+                '0'..='9'                   => self.lex_number()?,
+                '@'                         => self.lex_decorator(),
+                _ if is_symbol_start(car)   => self.lex_symbol(),
+                _                           => self.lex_operator()?,
+            }
+        }
+
+        // Comments after the last token have nothing left to lead, so they
+        // trail it instead.
+        let trivia = std::mem::take(&mut self.trivia);
+        if let Some(last) = self.tokens.last_mut() {
+            last.trailing.extend(trivia);
+        }
+
+        Ok(std::mem::take(&mut self.tokens))
+    }
+
+    /// Lexes a string literal delimited by double quotes, decoding escape
+    /// sequences. Strings may span several lines.
+    fn lex_string(&mut self) -> Result<(), LexerError> {
+        let (row, col) = (self.row, self.col);
+        let mut value = String::new();
+        self.next();
+
+        loop {
+            match self.peek() {
+                Some('"') => { self.next(); break; },
+                Some('\\') => value.push(self.lex_escape()?),
+                Some(car) => { value.push(car); self.next(); },
+                None => return Err(new_unterminated_str(row, col)),
+            }
+        }
+
+        self.push(TokenType::String(value), row, col);
+        Ok(())
+    }
+
+    /// Lexes an escape sequence inside a string literal, returning the
+    /// character it stands for. Errors point at the backslash.
+    fn lex_escape(&mut self) -> Result<char, LexerError> {
+        let (row, col) = (self.row, self.col);
+        self.next();
+        let car = match self.next() {
+            Some(car) => car,
+            None => return Err(new_invalid_escape('\\', row, col)),
+        };
+
+        match car {
+            'n'  => Ok('\n'),
+            't'  => Ok('\t'),
+            'r'  => Ok('\r'),
+            '0'  => Ok('\0'),
+            '"'  => Ok('"'),
+            '\\' => Ok('\\'),
+            'u'  => self.lex_unicode_escape(row, col),
+            _    => Err(new_invalid_escape(car, row, col)),
+        }
+    }
+
+    /// Lexes the `{...}` part of a `\u{...}` escape: one to six hex digits
+    /// naming a unicode scalar value.
+    fn lex_unicode_escape(&mut self, row: usize, col: usize) -> Result<char, LexerError> {
+        let mut digits = String::new();
+        if self.peek() != Some('{') {
+            return Err(new_invalid_unicode_escape(digits, row, col));
+        }
+        self.next();
+
+        loop {
+            match self.peek() {
+                Some('}') => { self.next(); break; },
+                Some(car) if car.is_ascii_hexdigit() && digits.len() < 6 => {
+                    digits.push(car);
+                    self.next();
+                },
+                _ => return Err(new_invalid_unicode_escape(digits, row, col)),
+            }
+        }
+
+        u32::from_str_radix(&digits, 16).ok()
+            .and_then(char::from_u32)
+            .ok_or(new_invalid_unicode_escape(digits, row, col))
+    }
+
+    /// Determines if the current `r` starts a raw string such as `r"..."` or
+    /// `r#"..."#` rather than a symbol.
+    fn is_raw_string(&self) -> bool {
+        let mut cursor = self.cursor + 1;
+        while self.chars.get(cursor) == Some(&'#') {
+            cursor += 1;
+        }
+        self.chars.get(cursor) == Some(&'"')
+    }
+
+    /// Lexes a raw string, in which backslashes carry no special meaning. Any
+    /// number of `#`s may surround the quotes so the string can itself
+    /// contain `"`: `r#"{"key": 1}"#`.
+    fn lex_raw_string(&mut self) -> Result<(), LexerError> {
+        let (row, col) = (self.row, self.col);
+        let mut value = String::new();
+        let mut hashes = 0;
+        self.next();
+        while self.peek() == Some('#') {
+            hashes += 1;
+            self.next();
+        }
+        self.next();
+
+        loop {
+            match self.next() {
+                Some('"') if self.closes_raw_string(hashes) => break,
+                Some(car) => value.push(car),
+                None => return Err(new_unterminated_str(row, col)),
+            }
+        }
+
+        self.push(TokenType::String(value), row, col);
+        Ok(())
+    }
+
+    /// Consumes the `#`s following a `"` in a raw string, returning whether
+    /// they close it.
+    fn closes_raw_string(&mut self, hashes: usize) -> bool {
+        let closing = (0..hashes).all(|i| self.chars.get(self.cursor + i) == Some(&'#'));
+        if closing {
+            for _ in 0..hashes { self.next(); }
+        }
+        closing
+    }
+
+    /// Lexes an integer or float literal. Integers may be written in hex
+    /// (`0xFF`), binary (`0b1010`) or octal (`0o17`), and any literal may use
+    /// `_` to separate digits: `1_000_000`. Decimal integers must fit in an
+    /// `i64`, while the other radixes may use the full 64 bits.
+    fn lex_number(&mut self) -> Result<(), LexerError> {
+        let (row, col) = (self.row, self.col);
+        let radix = match (self.peek(), self.peek_twice()) {
+            (Some('0'), Some('x' | 'X')) => 16,
+            (Some('0'), Some('b' | 'B')) => 2,
+            (Some('0'), Some('o' | 'O')) => 8,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.next();
+            self.next();
+        }
+
+        let mut text = self.read_digits(radix)?;
+        if text.is_empty() {
+            return Err(new_malformed_number(self.slice_from(col), row, col));
+        }
+        if radix != 10 {
+            // Radix literals spell out bits, so they may use all 64 of them:
+            // `0xFFFF_FFFF_FFFF_FFFF` is -1.
+            match u64::from_str_radix(&text, radix) {
+                Ok(i) => self.push(TokenType::Integer(i as i64), row, col),
+                Err(_) => return Err(new_number_overflow(self.slice_from(col), row, col)),
+            };
+            return Ok(());
+        }
+
+        let mut is_float = false;
+        if self.peek() == Some('.') && self.peek_twice().is_some_and(|c| c.is_ascii_digit()) {
+            self.next();
+            text.push('.');
+            text += &self.read_digits(10)?;
+            is_float = true;
+        }
+        if let Some('e' | 'E') = self.peek() {
+            self.next();
+            text.push('e');
+            if let Some(sign @ ('+' | '-')) = self.peek() {
+                self.next();
+                text.push(sign);
+            }
+            let exponent = self.read_digits(10)?;
+            if exponent.is_empty() {
+                return Err(new_malformed_number(self.slice_from(col), row, col));
+            }
+            text += &exponent;
+            is_float = true;
+        }
+
+        if is_float {
+            match text.parse::<f64>() {
+                Ok(f) if f.is_finite() => self.push(TokenType::Float(f), row, col),
+                _ => return Err(new_number_overflow(self.slice_from(col), row, col)),
+            };
+        } else {
+            match text.parse::<i64>() {
+                Ok(i) => self.push(TokenType::Integer(i), row, col),
+                Err(_) => return Err(new_number_overflow(self.slice_from(col), row, col)),
+            };
+        }
+        Ok(())
+    }
+
+    /// Reads the digits of a number in the given radix, skipping `_`
+    /// separators. Stops before an exponent in decimal literals.
+    fn read_digits(&mut self, radix: u32) -> Result<String, LexerError> {
+        let mut digits = String::new();
+        while let Some(car) = self.peek() {
+            if car == '_' {
+                self.next();
+            } else if car.is_digit(radix) {
+                digits.push(car);
+                self.next();
+            } else if radix == 10 && (car == 'e' || car == 'E') {
+                break;
+            } else if car.is_alphanumeric() {
+                return Err(new_invalid_digit(car, self.row, self.col));
+            } else {
+                break;
+            }
+        }
+        Ok(digits)
+    }
+
+    /// Returns the source text on the current row from column `col` up to the
+    /// cursor.
+    fn slice_from(&self, col: usize) -> String {
+        self.chars[self.cursor + col - self.col..self.cursor].iter().collect()
+    }
+
+    /// Lexes a decorator such as `@inline`. A lone `@` becomes an `At` token.
+    fn lex_decorator(&mut self) {
+        let (row, col) = (self.row, self.col);
+        self.next();
+        match self.peek() {
+            Some(car) if is_symbol_start(car) => {
+                let name = self.read_symbol();
+                self.push(TokenType::Decorator(name), row, col);
+            },
+            _ => self.push(TokenType::At, row, col),
+        };
+    }
+
+    /// Lexes a symbol, turning reserved words into their keyword tokens.
+    fn lex_symbol(&mut self) {
+        let (row, col) = (self.row, self.col);
+        let symbol = self.read_symbol();
+        self.push(keyword_or_symbol(symbol), row, col);
+    }
+
+    fn read_symbol(&mut self) -> String {
+        let mut symbol = String::new();
+        while let Some(car) = self.peek() {
+            if !is_symbol_char(car) { break; }
+            symbol.push(car);
+            self.next();
+        }
+        symbol
+    }
+
+    /// Lexes operators and delimiters, preferring the longest match.
+    fn lex_operator(&mut self) -> Result<(), LexerError> {
+        let (row, col) = (self.row, self.col);
+        let car = self.peek().unwrap();
+        let (typ, width) = match (car, self.peek_twice()) {
+            ('-', Some('-')) => { self.lex_line_comment(); return Ok(()); },
+            ('(', Some('-')) if self.opens_block_comment() => return self.lex_block_comment(),
+            _ if is_operator_char(car) => { self.lex_operator_run(); return Ok(()); },
+            (':', Some(':')) => (TokenType::DoubleColon,    2),
+            ('.', Some('.')) if self.peek_thrice() == Some('=') => (TokenType::DotDotEq, 3),
+            ('.', Some('.')) => (TokenType::DotDot,         2),
+            ('.', _)         => (TokenType::Dot,            1),
+            (',', _)         => (TokenType::Comma,          1),
+            (':', _)         => (TokenType::Colon,          1),
+            (';', _)         => (TokenType::Semicolon,      1),
+            ('(', _)         => (TokenType::LParen,         1),
+            (')', _)         => (TokenType::RParen,         1),
+            ('[', _)         => (TokenType::LBracket,       1),
+            (']', _)         => (TokenType::RBracket,       1),
+            ('{', _)         => (TokenType::LCurly,         1),
+            ('}', _)         => (TokenType::RCurly,         1),
+            _                => return Err(new_invalid_token(car, row, col)),
+        };
+
+        for _ in 0..width { self.next(); }
+        self.push(typ, row, col);
+        Ok(())
+    }
+
+    /// Lexes the longest run of operator characters. Runs which spell one of 
+    /// the built-in operators become that token, anything else becomes a 
+    /// user-defined `Operator`. A `--` always starts a comment, even in the 
+    /// middle of a run. A built-in operator followed by prefix operators, as
+    /// in `x=-1` or `a*-b`, is split after the built-in one instead.
+    fn lex_operator_run(&mut self) {
+        let (row, col) = (self.row, self.col);
+        let mut text = String::new();
+        let mut cursor = self.cursor;
+        while let Some(&car) = self.chars.get(cursor) {
+            if !is_operator_char(car) { break; }
+            if !text.is_empty() && car == '-' && self.chars.get(cursor + 1) == Some(&'-') { break; }
+            text.push(car);
+            cursor += 1;
+        }
+
+        let len = operator_run_len(&text);
+        for _ in 0..len { self.next(); }
+        self.push(operator_token(&text[..len]), row, col);
+    }
+
+    /// Lexes a `--` comment which runs until the end of the line.
+    fn lex_line_comment(&mut self) {
+        let (row, col) = (self.row, self.col);
+        let mut text = String::new();
+        while let Some(car) = self.peek() {
+            if car == '\n' { break; }
+            text.push(car);
+            self.next();
+        }
+
+        self.push_trivia(TriviaKind::LineComment, text, row, col);
+    }
+
+    /// Lexes a `(- ... -)` comment. Block comments nest, so every `(-` inside
+    /// the comment needs its own matching `-)`.
+    fn lex_block_comment(&mut self) -> Result<(), LexerError> {
+        let (row, col) = (self.row, self.col);
+        let mut text = String::new();
+        let mut depth = 0;
+
+        loop {
+            match (self.peek(), self.peek_twice()) {
+                (Some('('), Some('-')) if self.opens_block_comment() => depth += 1,
+                (Some('-'), Some(')')) => depth -= 1,
+                (Some(_), _) => {
+                    text.push(self.next().unwrap());
+                    continue;
+                }
+                (None, _) => return Err(new_unterminated_comment(row, col)),
+            };
+            text.push(self.next().unwrap());
+            text.push(self.next().unwrap());
+            if depth == 0 { break; }
+        }
+
+        // A comment spanning lines separates the tokens around it like a line
+        // break would.
+        self.newline |= text.contains('\n');
+        self.push_trivia(TriviaKind::BlockComment, text, row, col);
+        Ok(())
+    }
+
+    /// Determines if the `(-` at the cursor opens a block comment. When a 
+    /// digit, a symbol or another `(` follows right away, as in `f(-1)` or 
+    /// `(-x)`, it's a parenthesized negation instead.
+    fn opens_block_comment(&self) -> bool {
+        match self.peek_thrice() {
+            Some(car) => !is_symbol_char(car) && car != '(',
+            None => true,
+        }
+    }
+
+    /// Stores a comment as trailing trivia of the previous token when it
+    /// starts on the same line, or as leading trivia of the next token.
+    fn push_trivia(&mut self, kind: TriviaKind, text: String, row: usize, col: usize) {
+        let span = Span::new(self.file, self.start, self.offset);
+        let trivia = Trivia { kind, text, row, col, span };
+        match self.tokens.last_mut() {
+            Some(last) if last.row == row && self.trivia.is_empty() => {
+                last.trailing.push(trivia);
+            },
+            _ => self.trivia.push(trivia),
+        };
+    }
+
+    fn push(&mut self, typ: TokenType, row: usize, col: usize) {
+        let mut tok = Token::new(typ, row, col);
+        tok.span = Span::new(self.file, self.start, self.offset);
+        tok.leading = std::mem::take(&mut self.trivia);
+        tok.newline = std::mem::take(&mut self.newline);
+        self.tokens.push(tok);
+    }
+
+    /// Next returns an optional character and advances the cursor, keeping
+    /// track of the current row, column and byte offset.
+    fn next(&mut self) -> Option<char> {
+        let car = self.peek()?;
+        self.cursor += 1;
+        self.offset += car.len_utf8();
+        if car == '\n' {
+            self.row += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+        Some(car)
+    }
+
+    /// Peek returns an optional character without advancing the cursor.
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.cursor).copied()
+    }
+
+    fn peek_twice(&self) -> Option<char> {
+        self.chars.get(self.cursor + 1).copied()
+    }
+
+    fn peek_thrice(&self) -> Option<char> {
+        self.chars.get(self.cursor + 2).copied()
+    }
+}
+
+fn is_operator_char(car: char) -> bool {
+    "+-*/<>=!&|^~%$?".contains(car)
+}
+
+/// Returns the length of the first token in a run of operator characters. 
+/// That's the whole run, unless it spells a built-in operator followed only
+/// by the prefix operators `-`, `!` and `~`, which are lexed on their own.
+fn operator_run_len(text: &str) -> usize {
+    if !matches!(operator_token(text), TokenType::Operator(_)) {
+        return text.len();
+    }
+    for len in (1..text.len()).rev() {
+        let builtin = !matches!(operator_token(&text[..len]), TokenType::Operator(_));
+        if builtin && text[len..].chars().all(|car| "-!~".contains(car)) {
+            return len;
+        }
+    }
+
+    text.len()
+}
+
+/// Returns the token for a run of operator characters: one of the built-in
+/// operators if the run spells one, or a user-defined `Operator` otherwise.
+pub fn operator_token(text: &str) -> TokenType {
+    match text {
+        "+"     => TokenType::Plus,
+        "-"     => TokenType::Minus,
+        "*"     => TokenType::Star,
+        "/"     => TokenType::Slash,
+        "="     => TokenType::Bind,
+        "=="    => TokenType::Eq,
+        "!"     => TokenType::Not,
+        "!="    => TokenType::NotEq,
+        ">"     => TokenType::Greater,
+        ">="    => TokenType::GreaterEqual,
+        "<"     => TokenType::Less,
+        "<="    => TokenType::LessEqual,
+        "&&"    => TokenType::And,
+        "||"    => TokenType::Or,
+        "|>"    => TokenType::Pipe,
+        ">>"    => TokenType::ComposeRight,
+        "<<"    => TokenType::ComposeLeft,
+        "%"     => TokenType::Percent,
+        "**"    => TokenType::DoubleStar,
+        "&"     => TokenType::Ampersand,
+        "^"     => TokenType::Caret,
+        "~"     => TokenType::Tilde,
+        "<<<"   => TokenType::ShiftLeft,
+        ">>>"   => TokenType::ShiftRight,
+        "|"     => TokenType::Bar,
+        "->"    => TokenType::Arrow,
+        "=>"    => TokenType::EqArrow,
+        "<-"    => TokenType::LeftArrow,
+        _       => TokenType::Operator(text.to_string()),
+    }
+}
+
+fn is_symbol_start(car: char) -> bool {
+    car.is_alphabetic() || car == '_'
+}
+
+fn is_symbol_char(car: char) -> bool {
+    car.is_alphanumeric() || car == '_'
+}
+
+/// Returns the keyword token for a reserved word, or a symbol otherwise.
+fn keyword_or_symbol(symbol: String) -> TokenType {
+    match symbol.as_str() {
+        "let"       => TokenType::Let,
+        "mut"       => TokenType::Mut,
+        "fn"        => TokenType::Fn,
+        "if"        => TokenType::If,
+        "then"      => TokenType::Then,
+        "else"      => TokenType::Else,
+        "pub"       => TokenType::Pub,
+        "data"      => TokenType::Data,
+        "struct"    => TokenType::Struct,
+        "package"   => TokenType::Package,
+        "import"    => TokenType::Import,
+        "match"     => TokenType::Match,
+        "with"      => TokenType::With,
+        "while"     => TokenType::While,
+        "do"        => TokenType::Do,
+        "return"    => TokenType::Return,
+        "trait"     => TokenType::Trait,
+        "impl"      => TokenType::Impl,
+        "for"       => TokenType::For,
+        "in"        => TokenType::In,
+        "infixl"    => TokenType::Infixl,
+        "infixr"    => TokenType::Infixr,
+        "infix"     => TokenType::Infix,
+        "as"        => TokenType::As,
+        "where"     => TokenType::Where,
+        "type"      => TokenType::Type,
+        "newtype"   => TokenType::Newtype,
+        "hiding"    => TokenType::Hiding,
+        "None"      => TokenType::None,
+        "true"      => TokenType::Bool(true),
+        "false"     => TokenType::Bool(false),
+        _           => TokenType::Symbol(symbol),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(src: &str) -> Vec<TokenType> {
+        lex(src).unwrap().into_iter().map(|tok| tok.typ).collect()
+    }
+
+    fn symbol(name: &str) -> TokenType {
+        TokenType::Symbol(name.to_string())
+    }
+
+    #[test]
+    fn lexes_a_statement() {
+        assert_eq!(types("let x = 42;"), vec![
+            TokenType::Let, symbol("x"), TokenType::Bind, TokenType::Integer(42),
+            TokenType::Semicolon,
+        ]);
+    }
+
+    #[test]
+    fn prefers_the_longest_operator() {
+        assert_eq!(types("-> => :: == != <= >= && || : = < >"), vec![
+            TokenType::Arrow, TokenType::EqArrow, TokenType::DoubleColon,
+            TokenType::Eq, TokenType::NotEq, TokenType::LessEqual,
+            TokenType::GreaterEqual, TokenType::And, TokenType::Or,
+            TokenType::Colon, TokenType::Bind, TokenType::Less, TokenType::Greater,
+        ]);
+    }
+
+    #[test]
+    fn lexes_keywords_symbols_and_strings() {
+        assert_eq!(types("fn match with _x x1 \"hi there\""), vec![
+            TokenType::Fn, TokenType::Match, TokenType::With, symbol("_x"), 
+            symbol("x1"), TokenType::String("hi there".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn tracks_rows_and_columns() {
+        let positions: Vec<(usize, usize)> = lex("fn f\n  (x)").unwrap()
+            .iter()
+            .map(|tok| (tok.row, tok.col))
+            .collect();
+        assert_eq!(positions, vec![(1, 1), (1, 4), (2, 3), (2, 4), (2, 5)]);
+    }
+
+    #[test]
+    fn reports_invalid_tokens() {
+        assert_eq!(lex("let # = 1;").err(), Some(new_invalid_token('#', 1, 5)));
+    }
+
+    #[test]
+    fn reports_unterminated_strings() {
+        assert_eq!(lex("let s = \"abc").err(), Some(new_unterminated_str(1, 9)));
+    }
+
+    #[test]
+    fn keeps_comments_as_trivia() {
+        let toks = lex("x -- after x\n(- before (- nested -) y -)\ny").unwrap();
+        assert_eq!(toks.len(), 2);
+        assert_eq!(toks[0].trailing[0].kind, TriviaKind::LineComment);
+        assert_eq!(toks[0].trailing[0].text, "-- after x");
+        assert_eq!(toks[1].leading[0].kind, TriviaKind::BlockComment);
+        assert_eq!(toks[1].leading[0].text, "(- before (- nested -) y -)");
+        assert_eq!((toks[1].leading[0].row, toks[1].leading[0].col), (2, 1));
+    }
+
+    #[test]
+    fn marks_line_breaks_inside_block_comments() {
+        let newlines = |src| lex(src).unwrap().iter().map(|tok| tok.newline).collect::<Vec<_>>();
+        assert_eq!(newlines("a (- \n -) b"), vec![false, true]);
+        assert_eq!(newlines("a (- one line -) b"), vec![false, false]);
+        assert_eq!(newlines("a -- comment\nb"), vec![false, true]);
+    }
+
+    #[test]
+    fn trails_the_last_token_with_final_comments() {
+        let toks = lex("x\n-- the end").unwrap();
+        assert_eq!(toks[0].trailing[0].text, "-- the end");
+    }
+
+    #[test]
+    fn lexes_negation_in_parentheses() {
+        assert_eq!(types("f(-1) (-x) (-(y))"), vec![
+            symbol("f"), TokenType::LParen, TokenType::Minus, TokenType::Integer(1),
+            TokenType::RParen, TokenType::LParen, TokenType::Minus, symbol("x"),
+            TokenType::RParen, TokenType::LParen, TokenType::Minus, TokenType::LParen,
+            symbol("y"), TokenType::RParen, TokenType::RParen,
+        ]);
+        assert_eq!(types("(- f(-1) -) x"), vec![symbol("x")]);
+    }
+
+    #[test]
+    fn reports_unterminated_comments() {
+        assert_eq!(lex("x (- a (- b -)").err(), Some(new_unterminated_comment(1, 3)));
+    }
+
+    fn string(value: &str) -> TokenType {
+        TokenType::String(value.to_string())
+    }
+
+    #[test]
+    fn decodes_string_escapes() {
+        assert_eq!(types(r#""a\nb\t\"c\"\\ \u{1F600}\0""#), vec![
+            string("a\nb\t\"c\"\\ \u{1F600}\0"),
+        ]);
+    }
+
+    #[test]
+    fn lexes_raw_and_multi_line_strings() {
+        assert_eq!(types(r##"r"C:\dir" r#"{"key": 1}"# "two
+lines""##), vec![
+            string(r"C:\dir"), string(r#"{"key": 1}"#), string("two\nlines"),
+        ]);
+    }
+
+    #[test]
+    fn reports_bad_escapes() {
+        assert_eq!(lex(r#"x = "a\qb""#).err(), Some(new_invalid_escape('q', 1, 7)));
+        assert_eq!(
+            lex(r#""\u{110000}""#).err(), 
+            Some(new_invalid_unicode_escape("110000".to_string(), 1, 2)),
+        );
+        assert_eq!(
+            lex(r#""\u{12""#).err(), 
+            Some(new_invalid_unicode_escape("12".to_string(), 1, 2)),
+        );
+    }
+
+    #[test]
+    fn reports_unterminated_raw_strings() {
+        assert_eq!(lex(r##"r#"abc"  "##).err(), Some(new_unterminated_str(1, 1)));
+    }
+
+    #[test]
+    fn lexes_number_formats() {
+        assert_eq!(types("42 1_000_000 0xFF 0b1010 0o17 2.75 1e10 2.5e-3 1..2"), vec![
+            TokenType::Integer(42), TokenType::Integer(1_000_000), TokenType::Integer(255),
+            TokenType::Integer(10), TokenType::Integer(15), TokenType::Float(2.75),
+            TokenType::Float(1e10), TokenType::Float(2.5e-3), TokenType::Integer(1),
+            TokenType::DotDot, TokenType::Integer(2),
+        ]);
+    }
+
+    #[test]
+    fn lexes_radix_literals_with_all_64_bits() {
+        assert_eq!(types("0xFFFF_FFFF_FFFF_FFFF 0xcbf29ce484222325 0x7FFFFFFFFFFFFFFF"), vec![
+            TokenType::Integer(-1), TokenType::Integer(0xcbf29ce484222325_u64 as i64), 
+            TokenType::Integer(i64::MAX),
+        ]);
+    }
+
+    #[test]
+    fn reports_numbers_out_of_range() {
+        assert_eq!(
+            lex("x = 9223372036854775808").err(), 
+            Some(new_number_overflow("9223372036854775808".to_string(), 1, 5)),
+        );
+        assert_eq!(
+            lex("0x1_0000_0000_0000_0000").err(), 
+            Some(new_number_overflow("0x1_0000_0000_0000_0000".to_string(), 1, 1)),
+        );
+        assert_eq!(lex("1e400").err(), Some(new_number_overflow("1e400".to_string(), 1, 1)));
+    }
+
+    #[test]
+    fn reports_malformed_numbers() {
+        assert_eq!(lex("0x").err(), Some(new_malformed_number("0x".to_string(), 1, 1)));
+        assert_eq!(lex("1e+").err(), Some(new_malformed_number("1e+".to_string(), 1, 1)));
+        assert_eq!(lex("0b102").err(), Some(new_invalid_digit('2', 1, 5)));
+    }
+
+    #[test]
+    fn lexes_literal_keywords() {
+        assert_eq!(types("true false None Nonesuch"), vec![
+            TokenType::Bool(true), TokenType::Bool(false), TokenType::None, symbol("Nonesuch"),
+        ]);
+    }
+
+    #[test]
+    fn tracks_byte_spans() {
+        let spans: Vec<(usize, usize)> = lex("let é = \"ü\";\n-- c\nx").unwrap()
+            .iter()
+            .map(|tok| (tok.span.start, tok.span.end))
+            .collect();
+        assert_eq!(spans, vec![(0, 3), (4, 6), (7, 8), (9, 13), (13, 14), (20, 21)]);
+    }
+
+    #[test]
+    fn tags_spans_with_the_file_id() {
+        let toks = lex_file("a (- c -)", 3).unwrap();
+        assert_eq!(toks[0].span, Span::new(3, 0, 1));
+        assert_eq!(toks[0].trailing[0].span, Span::new(3, 2, 9));
+    }
+
+    fn operator(text: &str) -> TokenType {
+        TokenType::Operator(text.to_string())
+    }
+
+    #[test]
+    fn lexes_user_defined_operators() {
+        assert_eq!(types("a <+> b <-> c ++ d $ e"), vec![
+            symbol("a"), operator("<+>"), symbol("b"), operator("<->"), symbol("c"),
+            operator("++"), symbol("d"), operator("$"), symbol("e"),
+        ]);
+    }
+
+    #[test]
+    fn splits_prefix_operators_off_builtin_ones() {
+        assert_eq!(types("x=-1"), vec![
+            symbol("x"), TokenType::Bind, TokenType::Minus, TokenType::Integer(1),
+        ]);
+        assert_eq!(types("a*-b==!c"), vec![
+            symbol("a"), TokenType::Star, TokenType::Minus, symbol("b"),
+            TokenType::Eq, TokenType::Not, symbol("c"),
+        ]);
+        assert_eq!(types("a+-~b"), vec![
+            symbol("a"), TokenType::Plus, TokenType::Minus, TokenType::Tilde, symbol("b"),
+        ]);
+        assert_eq!(types("a--b"), vec![symbol("a")]);
+    }
+}
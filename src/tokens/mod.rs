@@ -1 +1,3 @@
-pub mod tokens;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod tokens;
+pub mod lexer;
\ No newline at end of file
@@ -5,6 +5,60 @@ pub struct Token {
     pub col: usize,
     pub row: usize,
     pub typ: TokenType,
+    pub span: Span,
+    pub leading: Vec<Trivia>,
+    pub trailing: Vec<Trivia>,
+    pub newline: bool,  /* a line break comes between this token and the previous one */
+}
+
+impl Token {
+    pub fn new(typ: TokenType, row: usize, col: usize) -> Token {
+        Token { 
+            typ, row, col, 
+            span: Span::default(), 
+            leading: Vec::new(), 
+            trailing: Vec::new(),
+            newline: false,
+        }
+    }
+}
+
+/// Span is the half-open range of byte offsets `[start, end)` covered by a
+/// token or node within the source file identified by `file`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Span {
+    pub file: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(file: usize, start: usize, end: usize) -> Span {
+        Span { file, start, end }
+    }
+
+    /// Returns the span running from the start of `self` to the end of `other`.
+    pub fn to(&self, other: Span) -> Span {
+        Span { file: self.file, start: self.start, end: other.end }
+    }
+
+    /// Converts the span into the 1-based `(row, col)` of its first character
+    /// and of the character just past its end.
+    pub fn line_col(&self, src: &str) -> ((usize, usize), (usize, usize)) {
+        (line_col(src, self.start), line_col(src, self.end))
+    }
+}
+
+/// Converts a byte offset into `src` into a 1-based `(row, col)` pair, where
+/// columns count characters rather than bytes.
+pub fn line_col(src: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(src.len());
+    let before = &src[..offset];
+    let row = 1 + before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let col = 1 + before[line_start..].chars().count();
+
+    (row, col)
 }
 
 impl fmt::Display for Token {
@@ -13,13 +67,36 @@ impl fmt::Display for Token {
     }
 }
 
+/// Trivia is source text the parser never sees, such as comments. It is kept
+/// on the surrounding tokens so that formatters and doc tooling can recover it.
+///
+/// A comment which starts on the same line as the previous token is attached
+/// to that token's `trailing` trivia, otherwise it becomes `leading` trivia
+/// of the next token.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+    pub row: usize,
+    pub col: usize,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TriviaKind {
+    LineComment,    /* -- ...     */
+    BlockComment,   /* (- ... -)  */
+}
+
 #[allow(dead_code)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenType {
     // Atoms
-    Integer(i32),   /* 42       */
+    Integer(i64),   /* 42       */
+    Float(f64),     /* 4.2      */
     String(String), /* "hello"  */
     Symbol(String), /* user-defined */
+    Bool(bool),     /* true     */
     None,           /* None     */
 
     // Operators
@@ -30,11 +107,19 @@ pub enum TokenType {
     NotEq,          /* != */    Greater,    /* >  */
     GreaterEqual,   /* >= */    Less,       /* <  */
     LessEqual,      /* <= */    And,        /* && */
-    Or,             /* || */
+    Or,             /* || */    Pipe,       /* |> */
+    ComposeRight,   /* >> */    ComposeLeft, /* << */
+    Percent,        /* %  */    DoubleStar, /* ** */
+    Ampersand,      /* &  */    Caret,      /* ^  */
+    Tilde,          /* ~  */    ShiftLeft,  /* <<< */
+    ShiftRight,     /* >>> */
+    Operator(String), /* <+> user-defined */
 
     // Delimeters
     Bar,            /*  |  */   Arrow,          /*  -> */
     EqArrow,        /*  => */   Dot,            /*  .  */
+    DotDot,         /*  .. */   DotDotEq,       /* ..= */
+    LeftArrow,      /*  <- */
     Comma,          /*  ,  */   Colon,          /*  :  */
     Semicolon,      /*  ;  */   DoubleColon,    /*  :: */
     LParen,         /*  (  */   RParen,         /*  )  */
@@ -53,11 +138,16 @@ pub enum TokenType {
     With,           /* with    */   While,      /* while   */   
     Do,             /* do      */   Return,     /* return  */
     Trait,          /* trait   */   Impl,       /* impl    */
-    For,            /* for     */
+    For,            /* for     */   In,         /* in      */
+    Infixl,         /* infixl  */   Infixr,     /* infixr  */
+    Infix,          /* infix   */   As,         /* as      */
+    Where,          /* where   */   Type,       /* type    */
+    Newtype,        /* newtype */   Hiding,     /* hiding  */
 
     // Decorators
     Decorator(String),
 
+    #[allow(clippy::upper_case_acronyms)]
     EOF,            /* end of file */
 }
 
@@ -66,8 +156,10 @@ impl fmt::Display for TokenType {
         match &self {
             // Atoms
             TokenType::Integer(i)   => write!(f, "Integer: {}", i),
+            TokenType::Float(x)     => write!(f, "Float: {}", x),
             TokenType::String(s)    => write!(f, "String: {}", s),
             TokenType::Symbol(s)    => write!(f, "Symbol: {}", s),
+            TokenType::Bool(b)      => write!(f, "Bool: {}", b),
             TokenType::None         => write!(f, "None"),
             
             // Operators
@@ -85,6 +177,17 @@ impl fmt::Display for TokenType {
             TokenType::LessEqual    => write!(f, "<="),
             TokenType::And          => write!(f, "&&"),
             TokenType::Or           => write!(f, "||"),
+            TokenType::Pipe         => write!(f, "|>"),
+            TokenType::ComposeRight => write!(f, ">>"),
+            TokenType::ComposeLeft  => write!(f, "<<"),
+            TokenType::Percent      => write!(f, "%"),
+            TokenType::DoubleStar   => write!(f, "**"),
+            TokenType::Ampersand    => write!(f, "&"),
+            TokenType::Caret        => write!(f, "^"),
+            TokenType::Tilde        => write!(f, "~"),
+            TokenType::ShiftLeft    => write!(f, "<<<"),
+            TokenType::ShiftRight   => write!(f, ">>>"),
+            TokenType::Operator(s)  => write!(f, "{}", s),
             
             // Delimeters
             TokenType::Bar          => write!(f, "|"),
@@ -92,6 +195,9 @@ impl fmt::Display for TokenType {
             TokenType::Arrow        => write!(f, "->"),
             TokenType::Comma        => write!(f, ","),
             TokenType::Dot          => write!(f, "."),
+            TokenType::DotDot       => write!(f, ".."),
+            TokenType::DotDotEq     => write!(f, "..="),
+            TokenType::LeftArrow    => write!(f, "<-"),
             TokenType::Semicolon    => write!(f, ";"),
             TokenType::Colon        => write!(f, ":"),
             TokenType::DoubleColon  => write!(f, "::"),
@@ -126,6 +232,15 @@ impl fmt::Display for TokenType {
             TokenType::Trait        => write!(f, "trait"),
             TokenType::Impl         => write!(f, "impl"),
             TokenType::For          => write!(f, "for"),
+            TokenType::In           => write!(f, "in"),
+            TokenType::Infixl       => write!(f, "infixl"),
+            TokenType::Infixr       => write!(f, "infixr"),
+            TokenType::Infix        => write!(f, "infix"),
+            TokenType::As           => write!(f, "as"),
+            TokenType::Where        => write!(f, "where"),
+            TokenType::Type         => write!(f, "type"),
+            TokenType::Newtype      => write!(f, "newtype"),
+            TokenType::Hiding       => write!(f, "hiding"),
             
             // Decorators
             TokenType::Decorator(s) => write!(f, "Decorator: {}", s),
@@ -134,3 +249,24 @@ impl fmt::Display for TokenType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_offsets_to_rows_and_columns() {
+        let src = "let é = 1\n  x";
+        assert_eq!(line_col(src, 0), (1, 1));
+        assert_eq!(line_col(src, 6), (1, 6));
+        assert_eq!(line_col(src, 11), (2, 1));
+        assert_eq!(line_col(src, 13), (2, 3));
+        assert_eq!(line_col(src, 99), (2, 4));
+    }
+
+    #[test]
+    fn converts_spans_to_rows_and_columns() {
+        let src = "a\nfoo(\n  1)";
+        assert_eq!(Span::new(0, 2, 11).line_col(src), ((2, 1), (3, 5)));
+    }
+}